@@ -1,8 +1,9 @@
 use crate::constants::{
     battle::{BattleState, EnemyAction, PlayerAction},
     monster::{ActionType, MonsterAction},
-    spell::Spell,
+    spell::{Spell, SpellInfo},
 };
+use crate::inventory::menu_lines;
 use crate::message::BattleMessages;
 use crate::monster::Monster;
 use crate::player::{ItemKind, Player, UnifiedItem};
@@ -16,6 +17,22 @@ use crate::utility::random_utils::{
 use crate::utility::spell_utils::{monster_action_effect, player_spell_effect};
 use rand::Rng;
 
+/// コマンド入力直前の戦闘状況（自動戦闘の判断材料）
+#[derive(Debug, Clone)]
+pub struct BattleSnapshot {
+    pub player_hp: u8,
+    pub player_max_hp: u8,
+    pub player_mp: u8,
+    pub player_sealed: bool,
+    pub spells: Vec<&'static SpellInfo>, // じゅもんメニューの 1.. に対応
+    pub items: Vec<UnifiedItem>,         // どうぐメニューの 1.. に対応
+    pub monster_id: u8,
+    pub monster_name: &'static str,
+    pub monster_hp: u8,
+    pub monster_asleep: bool,
+    pub monster_sealed: bool,
+}
+
 pub struct Battle<'a> {
     pub player: Player,
     pub monster: Monster,
@@ -23,6 +40,8 @@ pub struct Battle<'a> {
     pub monster_state: BattleState,
     pub messages: BattleMessages<'a>,
     pub input: &'a mut dyn PlayerInput,
+    pub earned_exp: u16,
+    pub earned_gold: u16,
 }
 
 impl<'a> Battle<'a> {
@@ -41,6 +60,8 @@ impl<'a> Battle<'a> {
             monster_state: BattleState::default(),
             messages: BattleMessages::new(player_name, monster_name, output),
             input,
+            earned_exp: 0,
+            earned_gold: 0,
         }
     }
 
@@ -70,6 +91,7 @@ impl<'a> Battle<'a> {
         } else if !self.monster_state.escaped {
             if self.player.is_alive() {
                 let gold = self.monster.get_gold();
+                self.earn_rewards(self.monster.stats.exp.into(), gold.into());
                 self.messages
                     .add_defeat_monster(self.monster.stats.exp.into(), gold.into());
             } else {
@@ -79,6 +101,14 @@ impl<'a> Battle<'a> {
         }
     }
 
+    /// 倒したモンスターの経験値・ゴールドを加算する（上限 65535）
+    pub fn earn_rewards(&mut self, exp: u16, gold: u16) {
+        self.earned_exp = exp;
+        self.earned_gold = gold;
+        self.player.exp = self.player.exp.saturating_add(exp);
+        self.player.gold = self.player.gold.saturating_add(gold);
+    }
+
     pub fn update_status(&mut self) {
         self.messages
             .add_status(self.player.hp, self.player.mp, self.monster.hp);
@@ -216,7 +246,7 @@ impl<'a> Battle<'a> {
     }
 
     fn handle_enemy_normal_attack(&mut self) {
-        let damage = self.monster.normal_damage(&self.player) as i16;
+        let damage = self.monster.battle_attack(&self.player) as i16;
 
         if damage > 0 {
             self.messages.add_monster_attack();
@@ -243,7 +273,10 @@ impl<'a> Battle<'a> {
 
     /// 敵: ギラ、ベギラマ
     fn handle_enemy_attack_spell(&mut self, spell: &Spell, monster_action: &MonsterAction) {
-        let damage = monster_action_effect(&monster_action.action);
+        // まほうのよろい・ロトのよろい は ギラ・ベギラマ を 2/3 にする
+        let damage = self
+            .player
+            .reduce_spell_damage(monster_action_effect(&monster_action.action));
         self.add_monster_spell(spell);
 
         if self.monster_state.seal {
@@ -445,13 +478,8 @@ impl<'a> Battle<'a> {
         self.messages.push("--- どうぐ ---".to_string());
         self.messages.push("0: もどる".to_string());
         let unified_item_list = self.player.unified_item_list();
-        for (i, item) in unified_item_list.iter().enumerate() {
-            if item.kind == ItemKind::Herb || item.kind == ItemKind::Key {
-                self.messages
-                    .push(format!("{}: {} （{}）", i + 1, item.name, item.count));
-            } else {
-                self.messages.push(format!("{}: {}", i + 1, item.name));
-            }
+        for line in menu_lines(&unified_item_list) {
+            self.messages.push(line);
         }
         self.messages.display();
         self.messages.clear();
@@ -545,7 +573,25 @@ impl<'a> Battle<'a> {
         }
     }
 
+    pub fn snapshot(&self) -> BattleSnapshot {
+        BattleSnapshot {
+            player_hp: self.player.hp,
+            player_max_hp: self.player.max_hp(),
+            player_mp: self.player.mp,
+            player_sealed: self.player_state.seal,
+            spells: self.player.spell_list(),
+            items: self.player.unified_item_list(),
+            monster_id: self.monster.id,
+            monster_name: self.monster.stats.name,
+            monster_hp: self.monster.hp,
+            monster_asleep: self.monster_state.sleep,
+            monster_sealed: self.monster_state.seal,
+        }
+    }
+
     pub fn commands(&mut self) {
+        let snapshot = self.snapshot();
+        self.input.observe_battle(&snapshot);
         let action = self.input.get_player_action(&mut || {
             self.messages.clear();
             self.messages.display_command();
@@ -569,6 +615,7 @@ impl<'a> Battle<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::calculator::monster_action;
     use crate::monster::Monster;
     use crate::player::{Player, PlayerArgs};
 
@@ -649,6 +696,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_enemy_normal_attack_uses_correction_formula() {
+        // はがねのよろい + みかがみのたて のゆうていにスライムは 0 ～ 1 ダメージ
+        let player = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            armor: Some(5),
+            shield: Some(3),
+            ..Default::default()
+        });
+        let monster = Monster::new(0);
+        let mut dummy_output = DummyOutput;
+        let mut dummy_input = DummyInput::new(vec![]);
+        let mut battle = Battle::new(player, monster, &mut dummy_input, &mut dummy_output);
+
+        for _ in 0..100 {
+            let before = battle.player.hp;
+            battle.handle_enemy_normal_attack();
+            assert!(before - battle.player.hp <= 1);
+            battle.player.hp_maximize();
+            battle.messages.clear();
+        }
+    }
+
+    #[test]
+    fn test_enemy_gira_is_reduced_by_magic_armor() {
+        let gira = MonsterAction {
+            ab_type: "B",
+            action: ActionType::Spell(Spell::Gira),
+            rate: 100,
+        };
+        // なし・まほうのよろい・ロトのよろい
+        for armor in [0, 6, 7] {
+            let player = Player::new_with(PlayerArgs {
+                name: Some("ゆうてい".to_string()),
+                level: Some(30),
+                armor: Some(armor),
+                ..Default::default()
+            });
+            let expected = monster_action(&player, &gira.action).unwrap();
+            let mut dummy_output = DummyOutput;
+            let mut dummy_input = DummyInput::new(vec![]);
+            let mut battle =
+                Battle::new(player, Monster::new(4), &mut dummy_input, &mut dummy_output);
+
+            let mut max_damage = 0;
+            for _ in 0..200 {
+                let before = battle.player.hp;
+                battle.handle_enemy_attack_spell(&Spell::Gira, &gira);
+                let damage = before - battle.player.hp;
+                // 計算機の範囲と 実際の戦闘が一致する
+                assert!((expected.min..=expected.max).contains(&damage));
+                max_damage = max_damage.max(damage);
+                battle.player.hp_maximize();
+                battle.messages.clear();
+            }
+            if armor == 0 {
+                assert!(max_damage > 6);
+            } else {
+                assert!(max_damage <= 6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_earn_rewards_saturates() {
+        let player = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            exp: Some(65530),
+            gold: Some(100),
+            ..Default::default()
+        });
+        let mut dummy_output = DummyOutput;
+        let mut dummy_input = DummyInput::new(vec![]);
+        let mut battle = Battle::new(player, Monster::new(0), &mut dummy_input, &mut dummy_output);
+
+        battle.earn_rewards(10, 2);
+        assert_eq!(battle.player.exp, 65535);
+        assert_eq!(battle.player.gold, 102);
+        assert_eq!((battle.earned_exp, battle.earned_gold), (10, 2));
+    }
+
     // fn setup_battle_for_action(enemy_action: EnemyAction) -> Battle {
     //     let player = Player::new("ゆうてい");
     //     let monster = Monster::new(0);
@@ -0,0 +1,313 @@
+use crate::constants::config::BIT_8_MAX;
+use crate::constants::monster::ActionType;
+use crate::constants::spell::Spell;
+use crate::monster::Monster;
+use crate::player::Player;
+use crate::utility::random_utils::{success_rate_by_percent, success_rate_by_ratio};
+use crate::utility::spell_utils::{monster_action_effect_with_rand, player_spell_effect_with_rand};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// かいしんのいちげきの発生率（1/32）
+pub const CRITICAL_RATIO: u16 = 32;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountProbability {
+    pub amount: u8,
+    pub probability: f64,
+}
+
+/// 1回の行動で発生するダメージ（回復量）の分布
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageReport {
+    pub label: String,
+    pub min: u8,       // 命中した時の最小値
+    pub max: u8,       // 命中した時の最大値
+    pub expected: f64, // ミスを含めた期待値
+    pub hit_rate: f64,
+    pub critical_rate: f64,
+    pub distribution: Vec<AmountProbability>, // ミスは 0 として含む
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchupReport {
+    pub player_name: String,
+    pub monster_name: String,
+    pub player_attack: DamageReport,
+    pub player_spells: Vec<DamageReport>,
+    pub monster_attack: DamageReport,
+    pub monster_actions: Vec<DamageReport>,
+}
+
+impl DamageReport {
+    /// landed: 命中時の (値, 確率)。確率の合計は hit_rate と一致する
+    fn from_landed(
+        label: impl Into<String>,
+        landed: BTreeMap<u8, f64>,
+        hit_rate: f64,
+        critical_rate: f64,
+    ) -> Self {
+        let min = landed.keys().next().copied().unwrap_or(0);
+        let max = landed.keys().next_back().copied().unwrap_or(0);
+
+        let mut outcomes = landed;
+        let miss_rate = 1.0 - hit_rate;
+        if miss_rate > 0.0 {
+            *outcomes.entry(0).or_insert(0.0) += miss_rate;
+        }
+        let expected = outcomes
+            .iter()
+            .map(|(&amount, &probability)| amount as f64 * probability)
+            .sum();
+
+        Self {
+            label: label.into(),
+            min,
+            max,
+            expected,
+            hit_rate,
+            critical_rate,
+            distribution: outcomes
+                .into_iter()
+                .map(|(amount, probability)| AmountProbability {
+                    amount,
+                    probability,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn probability_of(&self, amount: u8) -> f64 {
+        self.distribution
+            .iter()
+            .find(|p| p.amount == amount)
+            .map(|p| p.probability)
+            .unwrap_or(0.0)
+    }
+
+    pub fn pretty_string(&self) -> String {
+        format!(
+            "{}: {}～{} (期待値: {:.2} 命中率: {:.1}% かいしん: {:.1}%)",
+            self.label,
+            self.min,
+            self.max,
+            self.expected,
+            self.hit_rate * 100.0,
+            self.critical_rate * 100.0
+        )
+    }
+
+    pub fn distribution_string(&self) -> String {
+        self.distribution
+            .iter()
+            .map(|p| format!("{}={:.2}%", p.amount, p.probability * 100.0))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// 0～255 の乱数それぞれに対する結果を weight で重み付けして加算する
+fn add_rand_outcomes(outcomes: &mut BTreeMap<u8, f64>, weight: f64, f: impl Fn(u8) -> u8) {
+    if weight <= 0.0 {
+        return;
+    }
+    let per_rand = weight / (BIT_8_MAX as f64 + 1.0);
+    for rand_val in 0..=BIT_8_MAX {
+        *outcomes.entry(f(rand_val)).or_insert(0.0) += per_rand;
+    }
+}
+
+/// プレイヤー: こうげき（回避 → かいしん → 通常の順に判定）
+pub fn player_attack(player: &Player, monster: &Monster) -> DamageReport {
+    let hit_rate = 1.0 - success_rate_by_percent(monster.behavior.evade_rate as f64);
+    let critical_rate = if monster.is_final_boss() {
+        0.0
+    } else {
+        hit_rate * success_rate_by_ratio(CRITICAL_RATIO)
+    };
+
+    let mut landed = BTreeMap::new();
+    add_rand_outcomes(&mut landed, critical_rate, |r| {
+        player.critical_damage_with_rand(r)
+    });
+    add_rand_outcomes(&mut landed, hit_rate - critical_rate, |r| {
+        player.normal_damage_with_rand(monster, r)
+    });
+    DamageReport::from_landed("こうげき", landed, hit_rate, critical_rate)
+}
+
+/// プレイヤー: じゅもん（ダメージ・回復呪文のみ）
+pub fn player_spell(monster: &Monster, spell: Spell) -> Option<DamageReport> {
+    let hit_rate = match spell {
+        Spell::Gira | Spell::Begirama => {
+            1.0 - success_rate_by_percent(monster.behavior.resist.gira as f64)
+        }
+        Spell::Hoimi | Spell::Behoimi => 1.0,
+        _ => return None,
+    };
+
+    let mut landed = BTreeMap::new();
+    add_rand_outcomes(&mut landed, hit_rate, |r| {
+        player_spell_effect_with_rand(spell, r)
+    });
+    Some(DamageReport::from_landed(
+        spell.as_str(),
+        landed,
+        hit_rate,
+        0.0,
+    ))
+}
+
+/// 敵: こうげき（強い敵・弱い敵のダメージ式を選択）
+pub fn monster_attack(monster: &Monster, player: &Player) -> DamageReport {
+    let mut landed = BTreeMap::new();
+    add_rand_outcomes(&mut landed, 1.0, |r| {
+        monster.battle_attack_with_rand(player, r)
+    });
+    DamageReport::from_landed("こうげき", landed, 1.0, 0.0)
+}
+
+/// 敵: 呪文・特技（ギラ・ベギラマ・ほのおはよろいで軽減）
+pub fn monster_action(player: &Player, action: &ActionType) -> Option<DamageReport> {
+    let label = match action {
+        ActionType::Spell(spell) => spell.as_str(),
+        ActionType::Special(name) => name,
+    };
+
+    let mut landed = BTreeMap::new();
+    match action {
+        ActionType::Spell(Spell::Gira) | ActionType::Spell(Spell::Begirama) => {
+            add_rand_outcomes(&mut landed, 1.0, |r| {
+                player.reduce_spell_damage(monster_action_effect_with_rand(action, r))
+            });
+        }
+        ActionType::Special(name) if name.contains("ほのお") => {
+            add_rand_outcomes(&mut landed, 1.0, |r| {
+                player.reduce_fire_damage(monster_action_effect_with_rand(action, r))
+            });
+        }
+        ActionType::Spell(Spell::Hoimi) | ActionType::Spell(Spell::Behoimi) => {
+            add_rand_outcomes(&mut landed, 1.0, |r| {
+                monster_action_effect_with_rand(action, r)
+            });
+        }
+        _ => return None,
+    }
+    Some(DamageReport::from_landed(label, landed, 1.0, 0.0))
+}
+
+pub fn analyze_matchup(player: &Player, monster: &Monster) -> MatchupReport {
+    MatchupReport {
+        player_name: player.name.clone(),
+        monster_name: monster.name().to_string(),
+        player_attack: player_attack(player, monster),
+        player_spells: player
+            .spell_list()
+            .iter()
+            .filter_map(|info| player_spell(monster, info.spell))
+            .collect(),
+        monster_attack: monster_attack(monster, player),
+        monster_actions: monster
+            .behavior
+            .actions
+            .iter()
+            .filter_map(|action| monster_action(player, &action.action))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerArgs;
+
+    fn total_probability(report: &DamageReport) -> f64 {
+        report.distribution.iter().map(|p| p.probability).sum()
+    }
+
+    #[test]
+    fn test_player_attack_level_10_copper_sword_vs_wraith() {
+        // ゆうてい Lv10 どうのつるぎ → こうげき力 35 + 10 = 45
+        let player = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(10),
+            weapon: Some(3),
+            ..Default::default()
+        });
+        // しりょう（守備力 34 → 17）
+        let monster = Monster::new(15);
+        let report = player_attack(&player, &monster);
+
+        let base = player.attack_power() - monster.stats.defense / 2;
+        assert_eq!(report.min, base / 4);
+        assert_eq!(report.max, player.attack_power());
+        assert!((total_probability(&report) - 1.0).abs() < 1e-9);
+        assert!(report.hit_rate < 1.0);
+        assert!(report.critical_rate > 0.0);
+    }
+
+    #[test]
+    fn test_player_attack_no_critical_against_final_boss() {
+        let mut player = Player::new("ゆうてい");
+        player.maximize();
+        let report = player_attack(&player, &Monster::new(39));
+        assert_eq!(report.critical_rate, 0.0);
+    }
+
+    #[test]
+    fn test_player_spell_gira_resisted_by_metal_slime() {
+        let metal_slime = Monster::new(27);
+        let report = player_spell(&metal_slime, Spell::Gira).unwrap();
+        assert_eq!((report.min, report.max), (5, 11));
+        assert!(report.hit_rate < 0.1);
+        assert!(report.probability_of(0) > 0.9);
+        assert!(player_spell(&metal_slime, Spell::Rarirho).is_none());
+    }
+
+    #[test]
+    fn test_monster_attack_matches_battle_attack_range() {
+        let player = Player::new("ゆうてい");
+        let monster = Monster::new(0);
+        let report = monster_attack(&monster, &player);
+        assert_eq!(
+            (report.min, report.max),
+            monster.battle_attack_range(&player)
+        );
+        assert!((total_probability(&report) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_monster_fire_reduced_by_erdrick_armor() {
+        let fire = ActionType::Special("ほのお(強)");
+        let naked = Player::new("ゆうてい");
+        let armored = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            armor: Some(7),
+            ..Default::default()
+        });
+        let normal = monster_action(&naked, &fire).unwrap();
+        let reduced = monster_action(&armored, &fire).unwrap();
+        assert_eq!((normal.min, normal.max), (65, 71));
+        assert_eq!((reduced.min, reduced.max), (43, 47));
+        assert!(reduced.expected < normal.expected);
+    }
+
+    #[test]
+    fn test_analyze_matchup_lists_known_spells_and_monster_actions() {
+        let player = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(4),
+            ..Default::default()
+        });
+        // まほうつかい: ギラ
+        let report = analyze_matchup(&player, &Monster::new(4));
+        let spells: Vec<&str> = report
+            .player_spells
+            .iter()
+            .map(|r| r.label.as_str())
+            .collect();
+        assert_eq!(spells, vec!["ホイミ", "ギラ"]);
+        assert_eq!(report.monster_actions.len(), 1);
+        assert_eq!(report.monster_actions[0].label, "ギラ");
+    }
+}
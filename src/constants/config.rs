@@ -44,6 +44,8 @@ pub struct Cli {
     pub view: Option<Vec<String>>,
     #[clap(short, long)]
     pub option: Vec<String>,
+    #[clap(long, num_args = 0..=1, default_missing_value = "balanced")]
+    pub auto: Option<String>,
 }
 
 impl Cli {
@@ -82,6 +84,15 @@ pub enum Mode {
     Status,
     Display,
     Battle,
+    Damage,
+    Survival,
+    Gauntlet,
+    Shop,
+    Explore,
+    Route,
+    Service,
+    Equip,
+    Name,
 }
 
 impl Mode {
@@ -101,11 +112,21 @@ impl FromStr for Mode {
             "status" => Ok(Mode::Status),
             "display" => Ok(Mode::Display),
             "battle" => Ok(Mode::Battle),
+            "damage" => Ok(Mode::Damage),
+            "survival" => Ok(Mode::Survival),
+            "gauntlet" => Ok(Mode::Gauntlet),
+            "shop" => Ok(Mode::Shop),
+            "explore" => Ok(Mode::Explore),
+            "route" => Ok(Mode::Route),
+            "service" => Ok(Mode::Service),
+            "equip" => Ok(Mode::Equip),
+            "name" => Ok(Mode::Name),
             _ => Err(()),
         }
     }
 }
 
-pub const DISPLAY_CATEGORY_LIST: [&str; 7] = [
-    "item", "weapon", "armor", "shield", "status", "spell", "monster",
+pub const DISPLAY_CATEGORY_LIST: [&str; 12] = [
+    "item", "weapon", "armor", "shield", "status", "spell", "monster", "town", "message", "world",
+    "dungeon", "quest",
 ];
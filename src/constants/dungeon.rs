@@ -0,0 +1,202 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DungeonTile {
+    Wall,
+    Floor,
+    StairsUp,
+    StairsDown,
+    Door,
+    Chest,
+}
+
+impl DungeonTile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DungeonTile::Wall => "かべ",
+            DungeonTile::Floor => "ゆか",
+            DungeonTile::StairsUp => "のぼりかいだん",
+            DungeonTile::StairsDown => "くだりかいだん",
+            DungeonTile::Door => "とびら",
+            DungeonTile::Chest => "たからばこ",
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '#' => Some(DungeonTile::Wall),
+            '.' => Some(DungeonTile::Floor),
+            '<' => Some(DungeonTile::StairsUp),
+            '>' => Some(DungeonTile::StairsDown),
+            '+' => Some(DungeonTile::Door),
+            '$' => Some(DungeonTile::Chest),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            DungeonTile::Wall => '#',
+            DungeonTile::Floor => '.',
+            DungeonTile::StairsUp => '<',
+            DungeonTile::StairsDown => '>',
+            DungeonTile::Door => '+',
+            DungeonTile::Chest => '$',
+        }
+    }
+
+    /// かべ と 鍵のかかった とびら は通れない
+    pub fn is_walkable(&self) -> bool {
+        !matches!(self, DungeonTile::Wall | DungeonTile::Door)
+    }
+}
+
+pub const DUNGEON_TILE_LIST: [DungeonTile; 6] = [
+    DungeonTile::Wall,
+    DungeonTile::Floor,
+    DungeonTile::StairsUp,
+    DungeonTile::StairsDown,
+    DungeonTile::Door,
+    DungeonTile::Chest,
+];
+
+/// ダンジョンの定義（dungeon::parse_dungeon で読み込む）
+///
+/// dungeon <名前>                     … 1行目。名前は DUNGEON_ENCOUNTERS と同じ
+/// floor <階> <dark|lit>              … 続く行が その階のマップ（1文字 = 1マス、DungeonTile の記号）
+/// entrance <地上の入口名> <階> <x> <y> … WORLD_ENTRANCES から入ったときの位置
+/// exit <階> <x> <y> <地上x> <地上y>   … かいだん で地上へ出る
+/// stairs <階> <x> <y> <階> <x> <y>    … かいだん で行き来する（逆向きも自動で つながる）
+/// chest <階> <x> <y> <gold n|item id|weapon id|herb|key>
+pub const DUNGEON_DATA: [&str; 5] = [
+    "dungeon ロトのどうくつ
+floor 1 dark
+##########
+#<.#.....#
+#..#.###.#
+#..#...#.#
+#.####.#.#
+#......#>#
+##########
+floor 2 dark
+##########
+#<...#...#
+####.#.#.#
+#....#.#.#
+#.####.#.#
+#......#.#
+##########
+entrance ロトのどうくつ 1 1 1
+exit 1 1 1 28 12
+stairs 1 8 5 2 1 1",
+    "dungeon いわやまのどうくつ
+floor 1 dark
+############
+#<....#....#
+#.###.#.##.#
+#.#$..#..#.#
+#.#####.##.#
+#.......#>.#
+############
+floor 2 dark
+############
+#<.....#...#
+#.####.#.#.#
+#.#..#...#.#
+#.#$.#####.#
+#.##+#$....#
+#..........#
+############
+entrance いわやまのどうくつ 1 1 1
+exit 1 1 1 29 57
+stairs 1 9 5 2 1 1
+chest 1 3 3 herb
+chest 2 3 4 item 6
+chest 2 6 5 item 11",
+    "dungeon ガライのはか
+floor 1 dark
+##########
+#<......$#
+#.######.#
+#.#>...#.#
+#.####.#.#
+#......#.#
+##########
+floor 2 dark
+##########
+#...#...<#
+#.#.#.####
+#.#...#..#
+#.#####.##
+#>......$#
+##########
+floor 3 dark
+##########
+#<.......#
+########.#
+#$..+....#
+##########
+entrance ガライのはか 1 1 1
+exit 1 1 1 6 6
+stairs 1 3 3 2 8 1
+stairs 2 1 5 3 1 1
+chest 1 8 1 item 9
+chest 2 8 5 gold 120
+chest 3 1 3 item 10",
+    "dungeon ぬまちのどうくつ
+floor 1 dark
+#######
+#<....#
+#.###.#
+#.....#
+#.#####
+#.+...#
+#.#####
+#.....#
+#####.#
+#<....#
+#######
+entrance ぬまちのどうくつ（きた） 1 1 1
+entrance ぬまちのどうくつ（みなみ） 1 1 9
+exit 1 1 1 104 44
+exit 1 1 9 104 49",
+    "dungeon りゅうおうのしろ
+floor 1 lit
+#############
+#<....#....$#
+#.###.#.###.#
+#.#.....#...#
+#.#.#.#.#.#.#
+#...#$#...#>#
+#############
+floor 2 dark
+###########
+#<..#.....#
+###.#.###.#
+#...#.#$#.#
+#.###.#.#.#
+#.....#..>#
+###########
+floor 3 dark
+###########
+#>.......<#
+#.#######.#
+#.#.....#.#
+#...###...#
+###########
+floor 4 dark
+#########
+#.....$.#
+#.#####.#
+#.#<..+.#
+#.#####.#
+#.......#
+#########
+entrance りゅうおうのしろ 1 1 1
+exit 1 1 1 48 48
+stairs 1 11 5 2 1 1
+stairs 2 9 5 3 9 1
+stairs 3 1 1 4 3 3
+chest 1 11 1 herb
+chest 1 5 5 gold 500
+chest 2 7 3 item 1
+chest 4 6 1 weapon 7",
+];
@@ -0,0 +1,112 @@
+/// 地上は 15×15 マスごとの区画（8×8）に分かれ、区画ごとに出現するモンスターが決まる
+pub const ZONE_SIZE: usize = 15;
+pub const ZONE_COLUMNS: usize = 8;
+
+/// 区画 [y / ZONE_SIZE][x / ZONE_SIZE] の出現テーブル番号
+pub const WORLD_ZONE_MAP: [[u8; ZONE_COLUMNS]; 8] = [
+    [3, 3, 2, 2, 4, 5, 5, 5],
+    [3, 2, 1, 1, 4, 5, 5, 6],
+    [2, 1, 0, 0, 1, 4, 6, 6],
+    [7, 7, 13, 13, 9, 9, 8, 8],
+    [8, 8, 9, 10, 10, 9, 8, 9],
+    [10, 10, 11, 11, 10, 10, 11, 11],
+    [11, 11, 12, 12, 12, 12, 12, 11],
+    [12, 12, 12, 12, 13, 12, 13, 13],
+];
+
+/// 出現テーブル（MONSTER_MASTER の index、5 体から等確率で選ぶ）
+pub const ZONE_MONSTERS: [[u8; 5]; 14] = [
+    [0, 1, 0, 1, 0],
+    [1, 0, 2, 1, 2],
+    [2, 1, 3, 2, 3],
+    [3, 2, 4, 5, 3],
+    [4, 5, 6, 5, 4],
+    [5, 6, 7, 8, 6],
+    [8, 9, 7, 10, 9],
+    [10, 11, 12, 11, 10],
+    [12, 13, 14, 13, 15],
+    [15, 16, 17, 18, 16],
+    [17, 19, 20, 21, 22],
+    [22, 23, 24, 25, 27],
+    [25, 26, 28, 29, 30],
+    [29, 30, 31, 33, 34],
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DungeonEncounter {
+    pub name: &'static str, // WORLD_ENTRANCES の name
+    pub floor: u8,          // 地下 1 階 = 1
+    pub monsters: [u8; 5],
+}
+
+pub const DUNGEON_ENCOUNTERS: [DungeonEncounter; 12] = [
+    DungeonEncounter {
+        name: "ロトのどうくつ",
+        floor: 1,
+        monsters: [2, 3, 4, 5, 6],
+    },
+    DungeonEncounter {
+        name: "ロトのどうくつ",
+        floor: 2,
+        monsters: [3, 4, 5, 6, 7],
+    },
+    DungeonEncounter {
+        name: "ぬまちのどうくつ",
+        floor: 1,
+        monsters: [3, 4, 5, 6, 7],
+    },
+    DungeonEncounter {
+        name: "いわやまのどうくつ",
+        floor: 1,
+        monsters: [6, 7, 8, 9, 10],
+    },
+    DungeonEncounter {
+        name: "いわやまのどうくつ",
+        floor: 2,
+        monsters: [9, 10, 11, 12, 13],
+    },
+    DungeonEncounter {
+        name: "ガライのはか",
+        floor: 1,
+        monsters: [7, 8, 9, 10, 11],
+    },
+    DungeonEncounter {
+        name: "ガライのはか",
+        floor: 2,
+        monsters: [11, 12, 13, 14, 15],
+    },
+    DungeonEncounter {
+        name: "ガライのはか",
+        floor: 3,
+        monsters: [15, 16, 17, 18, 19],
+    },
+    DungeonEncounter {
+        name: "りゅうおうのしろ",
+        floor: 1,
+        monsters: [24, 25, 26, 28, 29],
+    },
+    DungeonEncounter {
+        name: "りゅうおうのしろ",
+        floor: 2,
+        monsters: [28, 29, 31, 33, 34],
+    },
+    DungeonEncounter {
+        name: "りゅうおうのしろ",
+        floor: 3,
+        monsters: [31, 33, 34, 35, 36],
+    },
+    DungeonEncounter {
+        name: "りゅうおうのしろ",
+        floor: 4,
+        monsters: [33, 34, 35, 36, 37],
+    },
+];
+
+/// 地形ごとの遭遇率（1/n）。町・城などの入口では遭遇しない（encounter_ratio が None）
+pub const GRASS_ENCOUNTER_RATIO: u16 = 24;
+pub const ROUGH_ENCOUNTER_RATIO: u16 = 16;
+pub const HILLS_ENCOUNTER_RATIO: u16 = 8;
+pub const DUNGEON_ENCOUNTER_RATIO: u16 = 16;
+
+/// トヘロス・せいすい の効果が続く歩数
+pub const REPEL_STEPS: u16 = 127;
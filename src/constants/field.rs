@@ -0,0 +1,15 @@
+/// レミーラで照らせる半径（マス）
+pub const REMIRA_LIGHT_RADIUS: u8 = 3;
+/// この歩数ごとに明かりの半径が 1 ずつ小さくなる
+pub const LIGHT_DECAY_STEPS: u16 = 40;
+/// たいまつで照らせる半径（マス）。ダンジョンを出るまで小さくならない
+pub const TORCH_LIGHT_RADIUS: u8 = 1;
+/// ロトのよろい は 1歩ごとに、まほうのよろい は この歩数ごとに HP が 1 回復する
+pub const ARMOR_REGEN_HP: u8 = 1;
+pub const MAGIC_ARMOR_REGEN_STEPS: u32 = 4;
+/// 経路探索で ダメージ床の HP 1 あたりに足すコスト
+pub const ROUTE_DAMAGE_COST: u32 = 5;
+/// 経路探索で 危険な区画を1歩進むたびに足すコスト
+pub const ROUTE_DANGER_COST: u32 = 10;
+/// この回数の通常攻撃で やられてしまう敵が出る区画を 危険とみなす
+pub const DANGER_HITS: u32 = 3;
@@ -1,3 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// みにつけると のろわれる どうぐ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CursedItem {
+    Belt,
+    Necklace,
+}
+
+impl CursedItem {
+    pub fn id(&self) -> u8 {
+        match self {
+            CursedItem::Belt => CURSED_BELT_ID,
+            CursedItem::Necklace => DEATH_NECKLACE_ID,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            CURSED_BELT_ID => Some(CursedItem::Belt),
+            DEATH_NECKLACE_ID => Some(CursedItem::Necklace),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        ITEM_MASTER[self.id() as usize].name
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Equipment {
     pub name: &'static str,
@@ -7,6 +37,43 @@ pub struct Equipment {
     pub defense: u8,
 }
 
+/// どうぐ欄（items）の数
+pub const MAX_ITEM_SLOTS: usize = 8;
+/// やくそう・かぎ はそれぞれ 6 個まで
+pub const MAX_HERBS: u8 = 6;
+pub const MAX_KEYS: u8 = 6;
+/// のろいのベルト・しのくびかざり
+pub const CURSED_BELT_ID: u8 = 9;
+pub const DEATH_NECKLACE_ID: u8 = 11;
+pub const CURSED_ITEM_IDS: [u8; 2] = [CURSED_BELT_ID, DEATH_NECKLACE_ID];
+/// しのくびかざり の のろいで 最大HP が 1/4 へる
+pub const DEATH_NECKLACE_HP_DIVISOR: u8 = 4;
+/// ITEM_MASTER の index
+pub const TORCH_ID: u8 = 1;
+pub const FAIRY_WATER_ID: u8 = 2;
+pub const CHIMERA_WING_ID: u8 = 3;
+pub const DRAGON_SCALE_ID: u8 = 4;
+pub const FAIRY_FLUTE_ID: u8 = 5;
+pub const ERDRICK_TOKEN_ID: u8 = 7;
+pub const PRINCESS_LOVE_ID: u8 = 8;
+pub const SILVER_HARP_ID: u8 = 10;
+pub const SUN_STONE_ID: u8 = 12;
+pub const STAFF_OF_RAIN_ID: u8 = 13;
+pub const RAINBOW_DROP_ID: u8 = 14;
+/// すてることが できない だいじなもの
+pub const QUEST_ITEM_IDS: [u8; 7] = [
+    FAIRY_FLUTE_ID,
+    ERDRICK_TOKEN_ID,
+    PRINCESS_LOVE_ID,
+    SILVER_HARP_ID,
+    SUN_STONE_ID,
+    STAFF_OF_RAIN_ID,
+    RAINBOW_DROP_ID,
+];
+/// unified_item_list で使う ID（やくそう・かぎ）
+pub const HERB_UNIFIED_ID: u8 = 101;
+pub const KEY_UNIFIED_ID: u8 = 102;
+
 pub const WEAPON_MASTER: [Equipment; 8] = [
     Equipment {
         name: "なし",
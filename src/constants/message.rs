@@ -0,0 +1,263 @@
+use crate::constants::item_weapon::PRINCESS_LOVE_ID;
+use crate::constants::quest::Milestone;
+
+/// せりふを言う条件（先に書いたものから順に調べる）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogueCondition {
+    Always,
+    Milestone(Milestone),    // その節目を済ませている
+    NotMilestone(Milestone), // その節目を まだ済ませていない
+    CursedBelt,              // のろいのベルト を そうびしている
+    CursedNecklace,          // しのくびかざり を もっている
+    HasItem(u8),             // ITEM_MASTER の index
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NpcLine {
+    pub condition: DialogueCondition,
+    pub text: &'static str, // {name} は ゆうしゃの なまえ
+}
+
+/// 町の人。town は TOWN_MASTER の name、(x, y) は町の中の位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Npc {
+    pub town: &'static str,
+    pub name: &'static str,
+    pub x: u8,
+    pub y: u8,
+    pub lines: &'static [NpcLine],
+}
+
+/// せりふに使える かな以外の記号（かなは KANA_TABLE と同じ決まりで 濁点を分けて調べる）
+pub const MESSAGE_SYMBOLS: [char; 7] = ['！', '？', '「', '」', '、', '。', '…'];
+/// ゆうしゃの なまえに置き換える目印
+pub const NAME_PLACEHOLDER: &str = "{name}";
+
+pub const NPC_MASTER: [Npc; 15] = [
+    Npc {
+        town: "ラダトームのしろ",
+        name: "おうさま",
+        x: 8,
+        y: 4,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::Milestone(Milestone::RescuePrincess),
+                text: "おお　{name}！　ろ－らを　すくってくれたこと　れいを　いうぞ。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "おお　{name}！　りゅうおうを　たおし　ひかりのたまを　とりもどすのじゃ！",
+            },
+        ],
+    },
+    Npc {
+        town: "ラダトームのしろ",
+        name: "へいし",
+        x: 6,
+        y: 9,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::NotMilestone(Milestone::RescuePrincess),
+                text: "ろ－らひめが　さらわれて　はや　はんとしが　すぎた……",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "ひめさまを　すくってくださり　ありがとうございます！",
+            },
+        ],
+    },
+    Npc {
+        town: "ラダトームのしろ",
+        name: "ろうじん",
+        x: 2,
+        y: 2,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::Milestone(Milestone::GetSunStone),
+                text: "たいようのいしを　みつけたようじゃな。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "この　しろの　ちかに　たいようのいしが　ねむっておる。",
+            },
+        ],
+    },
+    Npc {
+        town: "ラダトームのまち",
+        name: "むすめ",
+        x: 10,
+        y: 5,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::NotMilestone(Milestone::RescuePrincess),
+                text: "ひめさまは　ごぶじかしら……",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "ひめさまが　おもどりになって　まちは　おおよろこびよ！",
+            },
+        ],
+    },
+    Npc {
+        town: "ラダトームのまち",
+        name: "ろうじん",
+        x: 15,
+        y: 14,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::CursedBelt,
+                text: "おお　のろわれておるな！　わしが　のろいを　といてしんぜよう。",
+            },
+            NpcLine {
+                condition: DialogueCondition::CursedNecklace,
+                text: "おお　のろわれておるな！　わしが　のろいを　といてしんぜよう。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "ひがしの　ぬまちの　どうくつには　どらごんが　すんでおる。",
+            },
+        ],
+    },
+    Npc {
+        town: "ラダトームのまち",
+        name: "へいし",
+        x: 4,
+        y: 8,
+        lines: &[NpcLine {
+            condition: DialogueCondition::Always,
+            text: "まちの　そとでは　ぶきと　ぼうぐを　わすれずに！",
+        }],
+    },
+    Npc {
+        town: "ガライのまち",
+        name: "ぎんゆうしじん",
+        x: 6,
+        y: 6,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::NotMilestone(Milestone::GetSilverHarp),
+                text: "ぎんのたてごとは　がらいの　はかに　ねむっている。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "その　たてごとの　ねいろは　まものを　よびよせるのです。",
+            },
+        ],
+    },
+    Npc {
+        town: "ガライのまち",
+        name: "おとこ",
+        x: 9,
+        y: 3,
+        lines: &[NpcLine {
+            condition: DialogueCondition::Always,
+            text: "まほうの　かぎが　あれば　とびらを　ひらけるぞ。",
+        }],
+    },
+    Npc {
+        town: "マイラのむら",
+        name: "おんな",
+        x: 5,
+        y: 5,
+        lines: &[NpcLine {
+            condition: DialogueCondition::Always,
+            text: "ろとの　よろいは　この　むらの　どこかに　あるそうよ。",
+        }],
+    },
+    Npc {
+        town: "マイラのむら",
+        name: "おとこ",
+        x: 8,
+        y: 10,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::CursedBelt,
+                text: "その　べるとは　のろわれている！　らだと－むの　ろうじんを　たずねなさい。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "ここの　おんせんは　からだに　いいんだ。",
+            },
+        ],
+    },
+    Npc {
+        town: "リムルダール",
+        name: "ろうじん",
+        x: 4,
+        y: 4,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::NotMilestone(Milestone::FindErdrickToken),
+                text: "ろとの　しるしは　めるきどの　みなみひがしの　ぬまちに　うまっておる。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "ろとの　しるしを　もつ　ものこそ　まことの　ゆうしゃじゃ。",
+            },
+        ],
+    },
+    Npc {
+        town: "リムルダール",
+        name: "むすめ",
+        x: 10,
+        y: 8,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::HasItem(PRINCESS_LOVE_ID),
+                text: "ひめさまの　あいが　{name}さまを　みちびきますように。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "りゅうおうの　しろは　うみの　むこうに　みえるわ。",
+            },
+        ],
+    },
+    Npc {
+        town: "ドムドーラ",
+        name: "しょうにん",
+        x: 7,
+        y: 7,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::CursedNecklace,
+                text: "しの　くびかざりを　もっているな！　はやく　てばなしたほうが　いい。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "この　まちは　りゅうおうの　てしたに　ほろぼされてしまった……",
+            },
+        ],
+    },
+    Npc {
+        town: "メルキド",
+        name: "もんばん",
+        x: 5,
+        y: 2,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::Milestone(Milestone::DefeatGolem),
+                text: "ご－れむを　たおすとは！　ようこそ　めるきどへ！",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "まちの　いりぐちは　ご－れむが　まもっている。",
+            },
+        ],
+    },
+    Npc {
+        town: "メルキド",
+        name: "ろうじん",
+        x: 9,
+        y: 9,
+        lines: &[
+            NpcLine {
+                condition: DialogueCondition::NotMilestone(Milestone::GetRainbowDrop),
+                text: "にじのしずくが　あれば　りゅうおうの　しまへ　わたれる。",
+            },
+            NpcLine {
+                condition: DialogueCondition::Always,
+                text: "にじのはしを　わたり　りゅうおうを　たおすのじゃ！",
+            },
+        ],
+    },
+];
@@ -1,8 +1,15 @@
 pub mod battle;
 pub mod config;
+pub mod dungeon;
+pub mod encounter;
+pub mod field;
 pub mod item_weapon;
+pub mod message;
 pub mod monster;
+pub mod quest;
 pub mod save_data;
 pub mod spell;
 pub mod status;
 pub mod text;
+pub mod town;
+pub mod world;
@@ -0,0 +1,80 @@
+/// 物語の進み具合の節目
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Milestone {
+    DefeatDragon,
+    RescuePrincess,
+    FindErdrickToken,
+    GetSilverHarp,
+    GetStaffOfRain,
+    GetSunStone,
+    GetRainbowDrop,
+    DefeatGolem,
+    BuildRainbowBridge,
+    DefeatDragonlord,
+}
+
+impl Milestone {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Milestone::DefeatDragon => "ドラゴンを たおす",
+            Milestone::RescuePrincess => "ローラひめを たすける",
+            Milestone::FindErdrickToken => "ロトのしるしを みつける",
+            Milestone::GetSilverHarp => "ぎんのたてごとを てにいれる",
+            Milestone::GetStaffOfRain => "あまぐものつえを てにいれる",
+            Milestone::GetSunStone => "たいようのいしを てにいれる",
+            Milestone::GetRainbowDrop => "にじのしずくを てにいれる",
+            Milestone::DefeatGolem => "ゴーレムを たおす",
+            Milestone::BuildRainbowBridge => "にじのはしを かける",
+            Milestone::DefeatDragonlord => "りゅうおうを たおす",
+        }
+    }
+
+    /// どこで何をすればよいか
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Milestone::DefeatDragon => "ぬまちのどうくつの おくに いる",
+            Milestone::RescuePrincess => "ドラゴンの うしろに とらわれている",
+            Milestone::FindErdrickToken => "メルキドの みなみひがしの ぬまちを しらべる",
+            Milestone::GetSilverHarp => "ガライのはかの おくに ある",
+            Milestone::GetStaffOfRain => "ぎんのたてごとを あまぐものほこらへ もっていく",
+            Milestone::GetSunStone => "ラダトームのしろの ちかに ある",
+            Milestone::GetRainbowDrop => {
+                "ロトのしるし・たいようのいし・あまぐものつえ を せいなるほこらへ もっていく"
+            }
+            Milestone::DefeatGolem => "メルキドの いりぐちを まもっている",
+            Milestone::BuildRainbowBridge => {
+                "りゅうおうのしまの ひがしの きしで にじのしずくを つかう"
+            }
+            Milestone::DefeatDragonlord => "りゅうおうのしろの さいかそうに いる",
+        }
+    }
+
+    /// 先に済ませておく必要がある節目
+    pub fn prerequisites(&self) -> &'static [Milestone] {
+        match self {
+            Milestone::RescuePrincess => &[Milestone::DefeatDragon],
+            Milestone::GetStaffOfRain => &[Milestone::GetSilverHarp],
+            Milestone::GetRainbowDrop => &[
+                Milestone::FindErdrickToken,
+                Milestone::GetSunStone,
+                Milestone::GetStaffOfRain,
+            ],
+            Milestone::BuildRainbowBridge => &[Milestone::GetRainbowDrop],
+            Milestone::DefeatDragonlord => &[Milestone::BuildRainbowBridge],
+            _ => &[],
+        }
+    }
+}
+
+pub const MILESTONE_LIST: [Milestone; 10] = [
+    Milestone::DefeatDragon,
+    Milestone::RescuePrincess,
+    Milestone::FindErdrickToken,
+    Milestone::GetSilverHarp,
+    Milestone::GetStaffOfRain,
+    Milestone::GetSunStone,
+    Milestone::GetRainbowDrop,
+    Milestone::DefeatGolem,
+    Milestone::BuildRainbowBridge,
+    Milestone::DefeatDragonlord,
+];
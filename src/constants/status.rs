@@ -46,7 +46,7 @@ impl Display for Flags {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Status {
     pub level: u8,
     pub strength: u8,
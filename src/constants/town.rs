@@ -0,0 +1,108 @@
+/// やくそうの値段（ITEM_MASTER に含まれないため別に持つ）
+pub const HERB_PRICE: u16 = 24;
+pub const HERB_SELL: u16 = 12;
+
+/// 町・城で受けられるサービス
+/// weapons / armors / shields / items はそれぞれ WEAPON_MASTER / ARMOR_MASTER / SHIELD_MASTER / ITEM_MASTER の ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Town {
+    pub name: &'static str, // WORLD_ENTRANCES の name と対応
+    pub weapons: &'static [u8],
+    pub armors: &'static [u8],
+    pub shields: &'static [u8],
+    pub items: &'static [u8],
+    pub sells_herbs: bool,
+    pub inn_price: Option<u16>,
+    pub key_price: Option<u16>,
+    pub fairy_water: bool,
+}
+
+impl Town {
+    pub fn has_shop(&self) -> bool {
+        !(self.weapons.is_empty()
+            && self.armors.is_empty()
+            && self.shields.is_empty()
+            && self.items.is_empty()
+            && !self.sells_herbs)
+    }
+}
+
+pub const TOWN_MASTER: [Town; 7] = [
+    Town {
+        name: "ラダトームのしろ",
+        weapons: &[],
+        armors: &[],
+        shields: &[],
+        items: &[],
+        sells_herbs: false,
+        inn_price: None,
+        key_price: Some(85),
+        fairy_water: false,
+    },
+    Town {
+        name: "ラダトームのまち",
+        weapons: &[1, 2, 3],
+        armors: &[1, 2],
+        shields: &[1],
+        items: &[1, 4],
+        sells_herbs: true,
+        inn_price: Some(6),
+        key_price: None,
+        fairy_water: true,
+    },
+    Town {
+        name: "ガライのまち",
+        weapons: &[2, 3, 4],
+        armors: &[2, 3, 4],
+        shields: &[1],
+        items: &[1, 4],
+        sells_herbs: true,
+        inn_price: Some(25),
+        key_price: None,
+        fairy_water: false,
+    },
+    Town {
+        name: "マイラのむら",
+        weapons: &[2, 3, 5],
+        armors: &[2, 3, 4, 5],
+        shields: &[1, 2],
+        items: &[1, 3, 4],
+        sells_herbs: true,
+        inn_price: Some(20),
+        key_price: None,
+        fairy_water: true,
+    },
+    Town {
+        name: "リムルダール",
+        weapons: &[3, 4, 5],
+        armors: &[3, 4, 5],
+        shields: &[1, 2],
+        items: &[1, 3, 4],
+        sells_herbs: true,
+        inn_price: Some(55),
+        key_price: Some(53),
+        fairy_water: false,
+    },
+    Town {
+        name: "ドムドーラ",
+        weapons: &[],
+        armors: &[],
+        shields: &[],
+        items: &[],
+        sells_herbs: false,
+        inn_price: None,
+        key_price: None,
+        fairy_water: false,
+    },
+    Town {
+        name: "メルキド",
+        weapons: &[4, 5, 6],
+        armors: &[4, 5, 6],
+        shields: &[2],
+        items: &[3, 4],
+        sells_herbs: true,
+        inn_price: Some(100),
+        key_price: Some(98),
+        fairy_water: true,
+    },
+];
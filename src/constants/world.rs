@@ -0,0 +1,348 @@
+pub const WORLD_WIDTH: usize = 120;
+pub const WORLD_HEIGHT: usize = 120;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terrain {
+    Grass,
+    Desert,
+    Hills,
+    Forest,
+    Swamp,
+    Water,
+    Mountain,
+    Bridge,
+    Barrier,
+    Castle,
+    Town,
+    Cave,
+    Shrine,
+}
+
+impl Terrain {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Terrain::Grass => "そうげん",
+            Terrain::Desert => "さばく",
+            Terrain::Hills => "おか",
+            Terrain::Forest => "もり",
+            Terrain::Swamp => "どくのぬまち",
+            Terrain::Water => "うみ",
+            Terrain::Mountain => "やま",
+            Terrain::Bridge => "はし",
+            Terrain::Barrier => "バリア",
+            Terrain::Castle => "しろ",
+            Terrain::Town => "まち",
+            Terrain::Cave => "どうくつ",
+            Terrain::Shrine => "ほこら",
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Terrain::Grass),
+            ':' => Some(Terrain::Desert),
+            'n' => Some(Terrain::Hills),
+            'f' => Some(Terrain::Forest),
+            's' => Some(Terrain::Swamp),
+            '~' => Some(Terrain::Water),
+            'M' => Some(Terrain::Mountain),
+            '=' => Some(Terrain::Bridge),
+            'x' => Some(Terrain::Barrier),
+            'C' => Some(Terrain::Castle),
+            'T' => Some(Terrain::Town),
+            'D' => Some(Terrain::Cave),
+            'S' => Some(Terrain::Shrine),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            Terrain::Grass => '.',
+            Terrain::Desert => ':',
+            Terrain::Hills => 'n',
+            Terrain::Forest => 'f',
+            Terrain::Swamp => 's',
+            Terrain::Water => '~',
+            Terrain::Mountain => 'M',
+            Terrain::Bridge => '=',
+            Terrain::Barrier => 'x',
+            Terrain::Castle => 'C',
+            Terrain::Town => 'T',
+            Terrain::Cave => 'D',
+            Terrain::Shrine => 'S',
+        }
+    }
+
+    /// うみ・やま 以外は歩ける（どくのぬまち・バリアはダメージを受ける）
+    pub fn is_walkable(&self) -> bool {
+        !matches!(self, Terrain::Water | Terrain::Mountain)
+    }
+
+    pub fn is_entrance(&self) -> bool {
+        matches!(
+            self,
+            Terrain::Castle | Terrain::Town | Terrain::Cave | Terrain::Shrine
+        )
+    }
+}
+
+pub const TERRAIN_LIST: [Terrain; 13] = [
+    Terrain::Grass,
+    Terrain::Desert,
+    Terrain::Hills,
+    Terrain::Forest,
+    Terrain::Swamp,
+    Terrain::Water,
+    Terrain::Mountain,
+    Terrain::Bridge,
+    Terrain::Barrier,
+    Terrain::Castle,
+    Terrain::Town,
+    Terrain::Cave,
+    Terrain::Shrine,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entrance {
+    pub name: &'static str,
+    pub terrain: Terrain,
+    pub x: usize,
+    pub y: usize,
+}
+
+pub const WORLD_ENTRANCES: [Entrance; 15] = [
+    Entrance {
+        name: "ラダトームのしろ",
+        terrain: Terrain::Castle,
+        x: 43,
+        y: 43,
+    },
+    Entrance {
+        name: "ラダトームのまち",
+        terrain: Terrain::Town,
+        x: 48,
+        y: 41,
+    },
+    Entrance {
+        name: "ガライのまち",
+        terrain: Terrain::Town,
+        x: 2,
+        y: 2,
+    },
+    Entrance {
+        name: "マイラのむら",
+        terrain: Terrain::Town,
+        x: 104,
+        y: 10,
+    },
+    Entrance {
+        name: "リムルダール",
+        terrain: Terrain::Town,
+        x: 102,
+        y: 72,
+    },
+    Entrance {
+        name: "ドムドーラ",
+        terrain: Terrain::Town,
+        x: 25,
+        y: 89,
+    },
+    Entrance {
+        name: "メルキド",
+        terrain: Terrain::Town,
+        x: 73,
+        y: 102,
+    },
+    Entrance {
+        name: "りゅうおうのしろ",
+        terrain: Terrain::Castle,
+        x: 48,
+        y: 48,
+    },
+    Entrance {
+        name: "ロトのどうくつ",
+        terrain: Terrain::Cave,
+        x: 28,
+        y: 12,
+    },
+    Entrance {
+        name: "ぬまちのどうくつ（きた）",
+        terrain: Terrain::Cave,
+        x: 104,
+        y: 44,
+    },
+    Entrance {
+        name: "ぬまちのどうくつ（みなみ）",
+        terrain: Terrain::Cave,
+        x: 104,
+        y: 49,
+    },
+    Entrance {
+        name: "いわやまのどうくつ",
+        terrain: Terrain::Cave,
+        x: 29,
+        y: 57,
+    },
+    // 原作の ガライのはか は ガライのまち の中から入るが、ここでは町の中を作らないため
+    // まちの ちかくの どうくつ として地上に置いている（意図した簡略化）
+    Entrance {
+        name: "ガライのはか",
+        terrain: Terrain::Cave,
+        x: 6,
+        y: 6,
+    },
+    Entrance {
+        name: "あまぐものほこら",
+        terrain: Terrain::Shrine,
+        x: 81,
+        y: 1,
+    },
+    Entrance {
+        name: "せいなるほこら",
+        terrain: Terrain::Shrine,
+        x: 108,
+        y: 109,
+    },
+];
+
+/// にじのしずくで にじのはしが かかる場所
+pub const RAINBOW_BRIDGE_POSITION: (usize, usize) = (63, 49);
+/// にじのしずく を使う場所（はしの ひがしどなりの岸）
+pub const RAINBOW_DROP_POSITION: (usize, usize) = (64, 49);
+
+/// ロトのしるし が埋まっている場所（どくのぬまちの中）
+pub const ERDRICK_TOKEN_POSITION: (usize, usize) = (83, 112);
+
+/// のろわれていると 兵士に追い返される城
+pub const TANTEGEL_CASTLE: &str = "ラダトームのしろ";
+
+/// 1歩ごとに受けるダメージ
+pub const SWAMP_DAMAGE: u8 = 2;
+pub const BARRIER_DAMAGE: u8 = 15;
+
+/// アレフガルドの地上マップ（1文字 = 1マス、WORLD_MAP[y] の x 文字目）
+/// 町・城・洞窟の座標は原作の配置に合わせ、地形は簡略化して再現している
+/// （町の中から入る ガライのはか だけは 地上の どうくつ として置いている）
+pub const WORLD_MAP: [&str; WORLD_HEIGHT] = [
+    "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~",
+    "~~~~~~~~~~~~~~~~~~~~~~~~........~~~~~~~~~.....~~~~~~~~~~~~~~~~~~~~~~~~~~~~.......S..~~~~~~~~~...~~~~~~~~~~~~~~~~..~~~~~~",
+    "..T..~~........~~~~~~...............~...........~~~~~~~~~~.........~~~~~..............~~~~.........~~~~~~~~~~.........~~",
+    "...................................................~~~~...............~...............................~~~~............~~",
+    "......................................................................~...............................................~~",
+    "~~....................................................................~................................................~",
+    "~~....D...............................................................~................................................~",
+    "~~....................................................................~................................................~",
+    "~~....................................................ffffffffff......~................................................~",
+    "~~.................................................fffffffffffffff....~................................................~",
+    "~~...............................................ffffffffffffffffff...~.................................T..............~",
+    "~~..............................................fffffffffffffffffff...~................................................~",
+    "~~..........................D...................ffffffffffffffffffff..~................................................~",
+    "~~..............................................ffffffffffffffffffff..~.....................ssssssss...................~",
+    "~~................MM.............................ffffffffffffffffff...~....................ssssssssss..................~",
+    "~~.............MMMMMMMM...........................ffffffffffffffff....~...................ssssssssssss.................~",
+    "~~............MMMMMMMMMMM............................ffffffffffff.....~...................ssssssssssss.................~",
+    "~~...........MMMMMMMMMMMMMM..............................fffff........~....................ssssssssss.................~~",
+    "~~...........MMMMMMMMMMMMMMM..........................................~.................MMMMMMMsssss..................~~",
+    "~~..........MMMMMMMMMMMMMMMM..........................................~..............MMMMMMMMMMMMss...................~~",
+    "~~..........MMMMMMMMMMMMMMMM..........................................~...........MMMMMMMMMMMMMMMM....................~~",
+    "~~..........MMMMMMMMMMMMMMMM..........................................~.........MMMMMMMMMMMMMMMMMMM...................~~",
+    "~~...........MMMMMMMMMMMMMMM..........................................~........MMMMMMMMMMMMMMMMMMMMM..................~~",
+    "~~...........MMMMMMMMMMMMMM...........................................~........MMMMMMMMMMMMMMMMMMMMM..................~~",
+    "~~............MMMMMMMMMMMM............................................~.......MMMMMMMMMMMMMMMMMMMMMM..................~~",
+    "~~.............MMMMMMMMM..............................................=.......MMMMMMMMMMMMMMMMMMMMMMM.................~~",
+    "~~.................M.......nnnnnnn....................................~.......MMMMMMMMMMMMMMMMMMMMMM..................~~",
+    "~~.......................nnnnnnnnnnn..................................~.......MMMMMMMMMMMMMMMMMMMMMM..................~~",
+    "~.......................nnnnnnnnnnnnnn................................~........MMMMMMMMMMMMMMMMMMMMM...........::::...~~",
+    "~......................nnnnnnnnnnnnnnn................................~.........MMMMMMMMMMMMMMMMMMM...........::::::..~~",
+    "~......................nnnnnnnnnnnnnnn................................~...........MMMMMMMMMMMMMMMM...........::::::::.~~",
+    "~......................nnnnnnnnnnnnnnn................................~..............MMMMMMMMMMMM...........:::::::::.~~",
+    "~.......................nnnnnnnnnnnnn.................................~..................MMMMM..............::::::::::~~",
+    "~........fffffff..........nnnnnnnnnn..................................~....................................:::::::::::~~",
+    "~......fffffffffff..........nnnnn.....................................~....................................:::::::::::~~",
+    "~......ffffffffffff...................................................~.....................................::::::::::~~",
+    "~.....fffffffffffff...................................................~....nnnnnnnnnnnn.....................::::::::::~~",
+    "~~....fffffffffffff...................................................~..nnnnnnnnnnnnnnnn...................:::::::::.~~",
+    "~~.....fffffffffff....................................................~.nnnnnnnnnnnnnnnnn....................:::::::.~~~",
+    "~~......fffffffff.....................................................~..nnnnnnnnnnnnnnnn.....................:::::..~~~",
+    "~~....................................................................~....nnnnnnnnnnnn..............................~~~",
+    "~~..............................................T.....................~..........nnn.................................~~~",
+    "~~..............~~~~~~..............................~~~............~~~~~..............................~~.~~..........~~~",
+    "~~.........................................C..........................~..................................~~~~~.....~~~~~",
+    "~~~~~~~~~~~~~~~~~~~~~~~~~.......~~~~~~~~.......~~~~~~~~~~~~~~~~~~~~~~~~~~~~........~~~~~~~~.......~~~~~~D~~~~~~~~~~~~~~~",
+    "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~",
+    "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~...xx...........~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~",
+    "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~...xx...........~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~",
+    "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~.C.xxsssssss....~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~",
+    "~~~............~............................~~~...xxssssssss...~........................................D..............~",
+    "~~..........................................~~~...xxsssssssss..~.......................................................~",
+    "~~..........................................~~~...xxsssssssss..~.......................................................~",
+    "~~..........................................~~~...xxsssssssss..~.......................................................~",
+    "~~..........................................~~~xxxxxxxxxxxxxxxx~......................................................~~",
+    "~~~.........................................~~~...xxssssssss...~......................................................~~",
+    "~~~.........................................~~~...xx.sssss.....~.......................................nn.............~~",
+    "~~~.........................................~~~...xx...........~....................................nnnnnnnnn.........~~",
+    "~~~..........................D..............~~~~~~~~~~~~~~~~~~~~...................................nnnnnnnnnnnn.......~~",
+    "~~~.........................................~~~~~~~~~~~~~~~~~~~~..................................nnnnnnnnnnnnnn.....~~~",
+    "~~~...............................................................................................nnnnnnnnnnnnnnn....~~~",
+    "~~~........................................MMM....................................................nnnnnnnnnnnnnnn....~~~",
+    "~~~.....................................MMMMMMMMMMM...............................................nnnnnnnnnnnnnnn.....~~",
+    "~~....................................MMMMMMMMMMMMMMM.............................................nnnnnnnnnnnnnn......~~",
+    "~~...................................MMMMMMMMMMMMMMMMMM............................................nnnnnnnnnnnn.......~~",
+    "~~..................................MMMMMMMMMMMMMMMMMMMM............................................nnnnnnnnnn........~~",
+    "~~..................................MMMMMMMMMMMMMMMMMMMM...................:::........................nnnn............~~",
+    "~...................................MMMMMMMMMMMMMMMMMMMM................:::::::::.....................................~~",
+    "~....................................MMMMMMMMMMMMMMMMMM..............::::::::::::::...................................~~",
+    "~.....................................MMMMMMMMMMMMMMMM..............::::::::::::::::..................................~~",
+    "~.......................................MMMMMMMMMMM................::::::::::::::::::.................................~~",
+    "~.....................:::::::::............MMMM....................::::::::::::::::::.................................~~",
+    "~.................:::::::::::::::..................................::::::::::::::::::.................................~~",
+    "~.............::::::::::::::::::::.................................:::::::::::::::::..................T...............~~",
+    "~............::::::::::::::::::::::.................................:::::::::::::::...................................~~",
+    "~...........:::::::::::::::::::::::...................................::::::::::::...................................~~~",
+    "~...........::::::::::::::::::::::::.....................................:::::::.....................................~~~",
+    "~~.........::::::::::::::::::::::::::................................................................................~~~",
+    "~~.........::::::::::::::::::::::::::................................................................................~~~",
+    "~~.........::::::::::::::::::::::::::...............................................................................~~~~",
+    "~~.........::::::::::::::::::::::::::...............................................................................~~~~",
+    "~~.........:::::::::::::::::::~~~~~~~~~~~~~~~~~~~~=~~~~~~~~~~~~~~~~~~~..............................................~~~~",
+    "~~.........:::::::::::::::::::::::::::..............................................................................~~~~",
+    "~~.........::::::::::::::::::::::::::...............................................................................~~~~",
+    "~~..........:::::::::::::::::::::::::...............................................................................~~~~",
+    "~~..........:::::::::::::::::::::::::.....................fff.......................................................~~~~",
+    "~~...........:::::::::::::::::::::::....................ffffffff.....................................................~~~",
+    "~~............::::::::::::::::::::::...................ffffffffff....................................................~~~",
+    "~~..............:::::::::::::::::::...................ffffffffffff......................MMM..........................~~~",
+    "~..................:::::::::::::::....................fffffffffffff..................MMMMMMMMM........................~~",
+    "~.....................:::T:::::::....................ffffffffffffff.................MMMMMMMMMMM.......................~~",
+    "~.......................::::::::.....................ffffffffffffff................MMMMMMMMMMMMMM.....................~~",
+    "~....................................................ffffffffffffff...............MMMMMMMMMMMMMMMM....................~~",
+    "~~....................................................fffffffffffff..............MMMMMMMMMMMMMMMMMM....................~",
+    "~~....................................................ffffffffffff...............MMMMMMMMMMMMMMMMMMM...................~",
+    "~~.....................................................ffffffffff...............MMMMMMMMMMMMMMMMMMMM...................~",
+    "~~......................................................ffffffff................MMMMMMMMMMMMMMMMMMMM..................~~",
+    "~~.........................................................ff...................MMMMMMMMMMMMMMMMMMMM..................~~",
+    "~~~..............................................................................MMMMMMMMMMMMMMMMMMM..................~~",
+    "~~~..................................................................MMMM.MMMM...MMMMMMMMMMMMMMMMMM...................~~",
+    "~~~......fffffff.....................................................M.......M....MMMMMMMMMMMMMMMM....................~~",
+    "~~~....fffffffffff...................................................M.......M.....MMMMMMMMMMMMMM....................~~~",
+    "~~~...fffffffffffff..................................................M.......M......MMMMMMMMMMMM.....................~~~",
+    "~~~..ffffffffffffff..................................................M...T...M.......................................~~~",
+    "~~~..fffffffffffffff.................................................M.......M.......................................~~~",
+    "~~~..fffffffffffffff.................................................M.......M........................................~~",
+    "~~~..fffffffffffffff.................................................M.......M........................................~~",
+    "~~~..fffffffffffffff.................................................MMMMMMMMM........................................~~",
+    "~~...ffffffffffffff...................................................................................................~~",
+    "~~....ffffffffffff.................................................................ss.................................~~",
+    "~~.....ffffffffff...............................................................ssssssss....................S..........~",
+    "~~.......fffffff...............................................................ssssssssss..............................~",
+    "~.............................................................................ssssssssssss.............................~",
+    "~.............................................................................ssssssssssss.............................~",
+    "~.............................................................................ssssssssssss.............................~",
+    "~..............................................................................ssssssssss..............................~",
+    "~...............................................................................ssssssss..............................~~",
+    "~.................................................................................ssss................................~~",
+    "~..............................~~~~~..............................................~~~~................................~~",
+    "~.............~~~~~~........~~~~~~~~~~..........~~~~.............~~~~~~~.......~~~~~~~~~~...........~~.............~~~~~",
+    "~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~",
+];
@@ -0,0 +1,122 @@
+use crate::player::Player;
+use crate::town_service::remove_curse;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeathOptions {
+    pub keep_curse: bool, // のろいを解かずに そのまま生き返る
+}
+
+impl DeathOptions {
+    /// CLI の --option から作る。revive がなければ None（keep-curse で のろいを残す）
+    pub fn from_words(words: &[String]) -> Option<Self> {
+        words.iter().any(|word| word == "revive").then(|| Self {
+            keep_curse: words.iter().any(|word| word == "keep-curse"),
+        })
+    }
+}
+
+/// しんでしまったあと 王様の前で生き返った結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Revival {
+    pub name: String,
+    pub gold_lost: u16,
+    pub gold: u16, // 生き返ったあとの所持金
+    pub hp: u8,
+    pub mp: u8,
+    pub removed: Vec<String>, // のろいが解けて なくなった どうぐ
+    pub still_cursed: bool,
+}
+
+impl Revival {
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("おお {}！ しんでしまうとは なにごとだ！", self.name),
+            "そなたに もういちど きかいを あたえよう".to_string(),
+            format!(
+                "ゴールドが はんぶんに なった（-{}G のこり {}G）",
+                self.gold_lost, self.gold
+            ),
+        ];
+        lines.extend(
+            self.removed
+                .iter()
+                .map(|name| format!("{}の のろいが とけて なくなった", name)),
+        );
+        if self.still_cursed {
+            lines.push("しかし のろいは とけていない".to_string());
+        }
+        lines
+    }
+}
+
+/// 原作の死亡時のルール。ゴールドが半分になり、HP・MP が全回復して生き返る
+pub fn apply_death_penalty(player: &mut Player, options: &DeathOptions) -> Revival {
+    let gold_lost = player.gold - player.gold / 2;
+    player.gold /= 2;
+    // しのくびかざり の のろいが解けてから 全回復する
+    let removed = if options.keep_curse {
+        Vec::new()
+    } else {
+        remove_curse(player)
+            .map(|removal| removal.removed)
+            .unwrap_or_default()
+    };
+    player.hp_maximize();
+    player.mp_maximize();
+    Revival {
+        name: player.name.clone(),
+        gold_lost,
+        gold: player.gold,
+        hp: player.hp,
+        mp: player.mp,
+        removed,
+        still_cursed: player.is_cursed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::item_weapon::CURSED_ITEM_IDS;
+    use crate::player::PlayerArgs;
+
+    fn dead_player() -> Player {
+        let mut player = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(10),
+            gold: Some(101),
+            ..Default::default()
+        });
+        player.hp = 0;
+        player.mp = 0;
+        player.items[0] = CURSED_ITEM_IDS[1];
+        player.is_curse_necklace = true;
+        player
+    }
+
+    #[test]
+    fn test_apply_death_penalty() {
+        let mut player = dead_player();
+        let revival = apply_death_penalty(&mut player, &DeathOptions::default());
+        assert_eq!((revival.gold_lost, player.gold), (51, 50));
+        assert_eq!((player.hp, player.mp), (player.max_hp(), player.max_mp()));
+        assert_eq!(revival.removed.len(), 1);
+        assert!(!player.is_curse_necklace);
+        assert_eq!(player.items[0], 0);
+        assert!(!revival.still_cursed);
+    }
+
+    #[test]
+    fn test_apply_death_penalty_keeps_curse() {
+        let mut player = dead_player();
+        let words = ["revive".to_string(), "keep-curse".to_string()];
+        let options = DeathOptions::from_words(&words).unwrap();
+        assert_eq!(DeathOptions::from_words(&words[1..]), None);
+        let revival = apply_death_penalty(&mut player, &options);
+        assert!(revival.removed.is_empty());
+        assert!(revival.still_cursed);
+        assert_eq!(player.items[0], CURSED_ITEM_IDS[1]);
+        assert!(player.is_alive());
+    }
+}
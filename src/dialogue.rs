@@ -0,0 +1,163 @@
+use crate::constants::message::{
+    DialogueCondition, MESSAGE_SYMBOLS, NAME_PLACEHOLDER, NPC_MASTER, Npc,
+};
+use crate::constants::text::KANA_TABLE;
+use crate::player::Player;
+use crate::quest::QuestProgress;
+use crate::utility::string_utils::{join_dakuten, split_dakuten};
+
+impl DialogueCondition {
+    pub fn is_met(&self, player: &Player, progress: &QuestProgress) -> bool {
+        match self {
+            DialogueCondition::Always => true,
+            DialogueCondition::Milestone(milestone) => progress.is_done(*milestone),
+            DialogueCondition::NotMilestone(milestone) => !progress.is_done(*milestone),
+            DialogueCondition::CursedBelt => player.is_curse_belt,
+            DialogueCondition::CursedNecklace => player.is_curse_necklace,
+            DialogueCondition::HasItem(id) => player.items.contains(id),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            DialogueCondition::Always => "いつも".to_string(),
+            DialogueCondition::Milestone(milestone) => format!("{} あと", milestone.as_str()),
+            DialogueCondition::NotMilestone(milestone) => format!("{} まえ", milestone.as_str()),
+            DialogueCondition::CursedBelt => "のろいのベルト".to_string(),
+            DialogueCondition::CursedNecklace => "しのくびかざり".to_string(),
+            DialogueCondition::HasItem(id) => format!("どうぐ {}", id),
+        }
+    }
+}
+
+/// せりふが かな（濁点は分けて KANA_TABLE で調べる）と MESSAGE_SYMBOLS だけで書かれているか
+pub fn is_valid_message(text: &str) -> bool {
+    split_dakuten(&text.replace(NAME_PLACEHOLDER, ""))
+        .chars()
+        .all(|c| KANA_TABLE.contains(&c) || MESSAGE_SYMBOLS.contains(&c))
+}
+
+/// 町の名前と 町の人の名前で探す
+pub fn find_npc(town: &str, name: &str) -> Option<&'static Npc> {
+    NPC_MASTER
+        .iter()
+        .find(|npc| npc.town == town && npc.name == name)
+}
+
+/// 町の中の (x, y) にいる人
+pub fn npc_at(town: &str, x: u8, y: u8) -> Option<&'static Npc> {
+    NPC_MASTER
+        .iter()
+        .find(|npc| npc.town == town && npc.x == x && npc.y == y)
+}
+
+pub fn town_npcs(town: &str) -> Vec<&'static Npc> {
+    NPC_MASTER.iter().filter(|npc| npc.town == town).collect()
+}
+
+/// ゆうしゃの なまえ（4文字の空白を取り、濁点を戻す）を差し込む
+pub fn render_message(text: &str, player_name: &str) -> String {
+    let name = join_dakuten(player_name.trim_end_matches('　'));
+    text.replace(NAME_PLACEHOLDER, &name)
+}
+
+/// 条件に合う最初のせりふ
+pub fn speak(npc: &Npc, player: &Player) -> String {
+    let progress = QuestProgress::new(player, false);
+    npc.lines
+        .iter()
+        .find(|line| line.condition.is_met(player, &progress))
+        .map(|line| render_message(line.text, &player.name))
+        .unwrap_or_default()
+}
+
+/// 町の名前と 町の人の名前で せりふを引く
+pub fn lookup_message(town: &str, name: &str, player: &Player) -> Result<String, String> {
+    find_npc(town, name)
+        .map(|npc| speak(npc, player))
+        .ok_or_else(|| format!("Unknown NPC: {} {}", town, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::item_weapon::PRINCESS_LOVE_ID;
+    use crate::constants::quest::Milestone;
+    use crate::constants::town::TOWN_MASTER;
+    use crate::constants::world::{ERDRICK_TOKEN_POSITION, WORLD_ENTRANCES};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_catalog_is_valid() {
+        let mut keys = HashSet::new();
+        for npc in NPC_MASTER.iter() {
+            assert!(
+                TOWN_MASTER.iter().any(|t| t.name == npc.town),
+                "{}",
+                npc.town
+            );
+            assert!(
+                keys.insert((npc.town, npc.name)),
+                "{} {}",
+                npc.town,
+                npc.name
+            );
+            // 最後のせりふは いつでも言えるもの
+            assert_eq!(
+                npc.lines.last().map(|l| l.condition),
+                Some(DialogueCondition::Always)
+            );
+            for line in npc.lines.iter() {
+                assert!(is_valid_message(line.text), "{}", line.text);
+            }
+        }
+        assert!(!is_valid_message("ローラ"));
+        assert!(is_valid_message("{name}よ！"));
+    }
+
+    #[test]
+    fn test_conditional_lines() {
+        let mut player = Player::new("だい");
+        let king = lookup_message("ラダトームのしろ", "おうさま", &player).unwrap();
+        assert!(king.starts_with("おお　だい！　りゅうおうを"));
+
+        player.items[0] = PRINCESS_LOVE_ID;
+        let king = lookup_message("ラダトームのしろ", "おうさま", &player).unwrap();
+        assert!(king.contains("すくってくれた"));
+
+        let old_man = find_npc("ラダトームのまち", "ろうじん").unwrap();
+        assert!(!speak(old_man, &player).contains("のろわれて"));
+        player.is_curse_belt = true;
+        assert!(speak(old_man, &player).contains("のろわれて"));
+
+        assert_eq!(npc_at("メルキド", 5, 2).unwrap().name, "もんばん");
+        assert_eq!(town_npcs("ラダトームのしろ").len(), 3);
+        assert!(lookup_message("メルキド", "だれか", &player).is_err());
+    }
+
+    #[test]
+    fn test_erdrick_token_line_matches_position() {
+        let town = WORLD_ENTRANCES
+            .iter()
+            .find(|entrance| entrance.name == "メルキド")
+            .unwrap();
+        let (x, y) = ERDRICK_TOKEN_POSITION;
+        let vertical = if y > town.y { "みなみ" } else { "きた" };
+        let horizontal = if x > town.x { "ひがし" } else { "にし" };
+        let npc = NPC_MASTER
+            .iter()
+            .find(|npc| npc.town == "リムルダール" && npc.name == "ろうじん")
+            .unwrap();
+        let line = npc
+            .lines
+            .iter()
+            .find(|line| {
+                line.condition == DialogueCondition::NotMilestone(Milestone::FindErdrickToken)
+            })
+            .unwrap();
+        assert!(
+            line.text
+                .contains(&format!("めるきどの　{}{}の", vertical, horizontal))
+        );
+    }
+}
@@ -0,0 +1,500 @@
+use crate::constants::dungeon::{DUNGEON_DATA, DUNGEON_TILE_LIST, DungeonTile};
+use crate::constants::item_weapon::{ITEM_MASTER, WEAPON_MASTER};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChestContent {
+    Gold(u16),
+    Item(u8),   // ITEM_MASTER の index
+    Weapon(u8), // WEAPON_MASTER の index
+    Herb,
+    Key,
+}
+
+impl ChestContent {
+    pub fn name(&self) -> String {
+        match self {
+            ChestContent::Gold(gold) => format!("{}ゴールド", gold),
+            ChestContent::Item(id) => ITEM_MASTER[*id as usize].name.to_string(),
+            ChestContent::Weapon(id) => WEAPON_MASTER[*id as usize].name.to_string(),
+            ChestContent::Herb => "やくそう".to_string(),
+            ChestContent::Key => "まほうのかぎ".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chest {
+    pub floor: u8,
+    pub x: usize,
+    pub y: usize,
+    pub content: ChestContent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StairsTarget {
+    Floor { floor: u8, x: usize, y: usize },
+    World { x: usize, y: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stairs {
+    pub floor: u8,
+    pub x: usize,
+    pub y: usize,
+    pub target: StairsTarget,
+}
+
+/// 地上の入口から入ったときの位置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DungeonEntry {
+    pub entrance: String,
+    pub floor: u8,
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Floor {
+    pub number: u8,
+    pub dark: bool,
+    width: usize,
+    height: usize,
+    tiles: Vec<DungeonTile>,
+}
+
+impl Floor {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn tile_at(&self, x: usize, y: usize) -> Option<DungeonTile> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.tiles.get(y * self.width + x).copied()
+    }
+
+    pub fn is_walkable(&self, x: usize, y: usize) -> bool {
+        self.tile_at(x, y).is_some_and(|t| t.is_walkable())
+    }
+
+    pub fn set_tile(&mut self, x: usize, y: usize, tile: DungeonTile) {
+        if x < self.width && y < self.height {
+            self.tiles[y * self.width + x] = tile;
+        }
+    }
+
+    pub fn render(&self) -> Vec<String> {
+        self.render_visible(0, 0, None)
+    }
+
+    /// (cx, cy) から radius マス以内だけを描く（見えないマスは空白）。None なら全体
+    pub fn render_visible(&self, cx: usize, cy: usize, radius: Option<u8>) -> Vec<String> {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| {
+                        let visible = radius.is_none_or(|r| {
+                            x.abs_diff(cx) <= r as usize && y.abs_diff(cy) <= r as usize
+                        });
+                        match self.tile_at(x, y) {
+                            Some(tile) if visible => tile.to_char(),
+                            _ => ' ',
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dungeon {
+    pub name: String,
+    pub floors: Vec<Floor>,
+    pub entries: Vec<DungeonEntry>,
+    pub stairs: Vec<Stairs>,
+    pub chests: Vec<Chest>,
+}
+
+impl Dungeon {
+    pub fn floor(&self, number: u8) -> Option<&Floor> {
+        self.floors.iter().find(|f| f.number == number)
+    }
+
+    pub fn floor_mut(&mut self, number: u8) -> Option<&mut Floor> {
+        self.floors.iter_mut().find(|f| f.number == number)
+    }
+
+    pub fn stairs_at(&self, floor: u8, x: usize, y: usize) -> Option<&Stairs> {
+        self.stairs
+            .iter()
+            .find(|s| s.floor == floor && s.x == x && s.y == y)
+    }
+
+    pub fn chest_at(&self, floor: u8, x: usize, y: usize) -> Option<&Chest> {
+        self.chests
+            .iter()
+            .find(|c| c.floor == floor && c.x == x && c.y == y)
+    }
+
+    pub fn entry(&self, entrance: &str) -> Option<&DungeonEntry> {
+        self.entries.iter().find(|e| e.entrance == entrance)
+    }
+}
+
+fn parse_numbers<const N: usize>(fields: &[&str], line_no: usize) -> Result<[usize; N], String> {
+    if fields.len() < N {
+        return Err(format!("Line {}: expected {} numbers", line_no, N));
+    }
+    let mut numbers = [0; N];
+    for (i, field) in fields.iter().take(N).enumerate() {
+        numbers[i] = field
+            .parse()
+            .map_err(|_| format!("Line {}: invalid number: {}", line_no, field))?;
+    }
+    Ok(numbers)
+}
+
+fn parse_chest_content(fields: &[&str], line_no: usize) -> Result<ChestContent, String> {
+    let value = |i: usize| -> Result<usize, String> {
+        fields
+            .get(i)
+            .and_then(|f| f.parse().ok())
+            .ok_or_else(|| format!("Line {}: invalid chest content", line_no))
+    };
+    match fields.first().copied() {
+        Some("gold") => Ok(ChestContent::Gold(value(1)? as u16)),
+        Some("item") if value(1)? < ITEM_MASTER.len() => Ok(ChestContent::Item(value(1)? as u8)),
+        Some("weapon") if value(1)? < WEAPON_MASTER.len() => {
+            Ok(ChestContent::Weapon(value(1)? as u8))
+        }
+        Some("herb") => Ok(ChestContent::Herb),
+        Some("key") => Ok(ChestContent::Key),
+        _ => Err(format!("Line {}: invalid chest content", line_no)),
+    }
+}
+
+fn finish_floor(
+    floors: &mut Vec<Floor>,
+    number: u8,
+    dark: bool,
+    rows: &[&str],
+) -> Result<(), String> {
+    let width = rows.first().map_or(0, |row| row.chars().count());
+    if width == 0 || rows.iter().any(|row| row.chars().count() != width) {
+        return Err(format!("Floor {}: rows must have the same width", number));
+    }
+    let tiles = rows
+        .iter()
+        .flat_map(|row| row.chars())
+        .map(|c| {
+            DungeonTile::from_char(c)
+                .ok_or_else(|| format!("Floor {}: unknown tile: {}", number, c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    floors.push(Floor {
+        number,
+        dark,
+        width,
+        height: rows.len(),
+        tiles,
+    });
+    Ok(())
+}
+
+/// DUNGEON_DATA の書式を読み込む
+pub fn parse_dungeon(source: &str) -> Result<Dungeon, String> {
+    let mut lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()));
+    let name = match lines.next() {
+        Some((_, line)) if line.starts_with("dungeon ") => {
+            line["dungeon ".len()..].trim().to_string()
+        }
+        _ => return Err("Line 1: expected 'dungeon <name>'".to_string()),
+    };
+    let mut dungeon = Dungeon {
+        name,
+        floors: Vec::new(),
+        entries: Vec::new(),
+        stairs: Vec::new(),
+        chests: Vec::new(),
+    };
+    let mut current: Option<(u8, bool, Vec<&str>)> = None;
+
+    for (line_no, line) in lines {
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with(|c| DUNGEON_TILE_LIST.iter().any(|t| t.to_char() == c)) {
+            match current.as_mut() {
+                Some((_, _, rows)) => rows.push(line),
+                None => return Err(format!("Line {}: map row outside of a floor", line_no)),
+            }
+            continue;
+        }
+        if let Some((number, dark, rows)) = current.take() {
+            finish_floor(&mut dungeon.floors, number, dark, &rows)?;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[0] {
+            "floor" => {
+                let [number] = parse_numbers::<1>(&fields[1..], line_no)?;
+                let dark = match fields.get(2).copied() {
+                    Some("dark") => true,
+                    Some("lit") => false,
+                    _ => return Err(format!("Line {}: expected dark or lit", line_no)),
+                };
+                current = Some((number as u8, dark, Vec::new()));
+            }
+            "entrance" => {
+                let entrance = fields
+                    .get(1)
+                    .ok_or_else(|| format!("Line {}: expected entrance name", line_no))?;
+                let [floor, x, y] = parse_numbers::<3>(&fields[2..], line_no)?;
+                dungeon.entries.push(DungeonEntry {
+                    entrance: entrance.to_string(),
+                    floor: floor as u8,
+                    x,
+                    y,
+                });
+            }
+            "exit" => {
+                let [floor, x, y, wx, wy] = parse_numbers::<5>(&fields[1..], line_no)?;
+                dungeon.stairs.push(Stairs {
+                    floor: floor as u8,
+                    x,
+                    y,
+                    target: StairsTarget::World { x: wx, y: wy },
+                });
+            }
+            "stairs" => {
+                let [floor, x, y, to_floor, tx, ty] = parse_numbers::<6>(&fields[1..], line_no)?;
+                dungeon.stairs.push(Stairs {
+                    floor: floor as u8,
+                    x,
+                    y,
+                    target: StairsTarget::Floor {
+                        floor: to_floor as u8,
+                        x: tx,
+                        y: ty,
+                    },
+                });
+                dungeon.stairs.push(Stairs {
+                    floor: to_floor as u8,
+                    x: tx,
+                    y: ty,
+                    target: StairsTarget::Floor {
+                        floor: floor as u8,
+                        x,
+                        y,
+                    },
+                });
+            }
+            "chest" => {
+                let [floor, x, y] = parse_numbers::<3>(&fields[1..], line_no)?;
+                dungeon.chests.push(Chest {
+                    floor: floor as u8,
+                    x,
+                    y,
+                    content: parse_chest_content(&fields[4..], line_no)?,
+                });
+            }
+            keyword => return Err(format!("Line {}: unknown keyword: {}", line_no, keyword)),
+        }
+    }
+    if let Some((number, dark, rows)) = current.take() {
+        finish_floor(&mut dungeon.floors, number, dark, &rows)?;
+    }
+    validate(&dungeon)?;
+    Ok(dungeon)
+}
+
+/// 入口・かいだん・たからばこ がマップ上の正しいマスを指しているか
+fn validate(dungeon: &Dungeon) -> Result<(), String> {
+    let tile = |floor: u8, x: usize, y: usize| dungeon.floor(floor).and_then(|f| f.tile_at(x, y));
+    for entry in dungeon.entries.iter() {
+        if !tile(entry.floor, entry.x, entry.y).is_some_and(|t| t.is_walkable()) {
+            return Err(format!(
+                "{}: invalid entrance {}",
+                dungeon.name, entry.entrance
+            ));
+        }
+    }
+    for stairs in dungeon.stairs.iter() {
+        if !matches!(
+            tile(stairs.floor, stairs.x, stairs.y),
+            Some(DungeonTile::StairsUp | DungeonTile::StairsDown)
+        ) {
+            return Err(format!(
+                "{}: no stairs at floor {} ({}, {})",
+                dungeon.name, stairs.floor, stairs.x, stairs.y
+            ));
+        }
+    }
+    for chest in dungeon.chests.iter() {
+        if tile(chest.floor, chest.x, chest.y) != Some(DungeonTile::Chest) {
+            return Err(format!(
+                "{}: no chest at floor {} ({}, {})",
+                dungeon.name, chest.floor, chest.x, chest.y
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// ダンジョンの記号の凡例
+pub fn legend() -> String {
+    DUNGEON_TILE_LIST
+        .iter()
+        .map(|t| format!("{}={}", t.to_char(), t.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn load_dungeons() -> Result<Vec<Dungeon>, String> {
+    DUNGEON_DATA
+        .iter()
+        .map(|source| parse_dungeon(source))
+        .collect()
+}
+
+/// 地上の入口名から ダンジョンと入ったときの位置を探す
+pub fn find_dungeon_entry<'a>(
+    dungeons: &'a [Dungeon],
+    entrance: &str,
+) -> Option<(&'a Dungeon, &'a DungeonEntry)> {
+    dungeons
+        .iter()
+        .find_map(|dungeon| dungeon.entry(entrance).map(|entry| (dungeon, entry)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::encounter::DUNGEON_ENCOUNTERS;
+    use crate::constants::world::WORLD_ENTRANCES;
+    use std::collections::{HashSet, VecDeque};
+
+    /// とびら も通れるものとして、かいだん を含めて歩いて行けるマス
+    fn reachable(dungeon: &Dungeon, floor: u8, x: usize, y: usize) -> HashSet<(u8, usize, usize)> {
+        let mut seen = HashSet::from([(floor, x, y)]);
+        let mut queue = VecDeque::from([(floor, x, y)]);
+        while let Some((f, x, y)) = queue.pop_front() {
+            let mut next = vec![
+                (f, x + 1, y),
+                (f, x.wrapping_sub(1), y),
+                (f, x, y + 1),
+                (f, x, y.wrapping_sub(1)),
+            ];
+            if let Some(Stairs {
+                target: StairsTarget::Floor { floor, x, y },
+                ..
+            }) = dungeon.stairs_at(f, x, y)
+            {
+                next.push((*floor, *x, *y));
+            }
+            for (nf, nx, ny) in next {
+                let passable = dungeon
+                    .floor(nf)
+                    .and_then(|m| m.tile_at(nx, ny))
+                    .is_some_and(|t| t != DungeonTile::Wall);
+                if passable && seen.insert((nf, nx, ny)) {
+                    queue.push_back((nf, nx, ny));
+                }
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn test_all_dungeons_load() {
+        let dungeons = load_dungeons().unwrap();
+        assert_eq!(dungeons.len(), DUNGEON_DATA.len());
+        for dungeon in dungeons.iter() {
+            for floor in dungeon.floors.iter() {
+                assert!(
+                    DUNGEON_ENCOUNTERS
+                        .iter()
+                        .any(|d| d.name == dungeon.name && d.floor == floor.number),
+                    "{} {}",
+                    dungeon.name,
+                    floor.number
+                );
+            }
+            for entry in dungeon.entries.iter() {
+                let entrance = WORLD_ENTRANCES
+                    .iter()
+                    .find(|e| e.name == entry.entrance)
+                    .unwrap();
+                // 入口からは 地上の同じ入口へ戻れる
+                assert!(dungeon.stairs.iter().any(|s| s.target
+                    == StairsTarget::World {
+                        x: entrance.x,
+                        y: entrance.y
+                    }));
+            }
+        }
+    }
+
+    #[test]
+    fn test_everything_is_reachable_from_entrance() {
+        for dungeon in load_dungeons().unwrap().iter() {
+            let entry = &dungeon.entries[0];
+            let seen = reachable(dungeon, entry.floor, entry.x, entry.y);
+            for stairs in dungeon.stairs.iter() {
+                assert!(
+                    seen.contains(&(stairs.floor, stairs.x, stairs.y)),
+                    "{}",
+                    dungeon.name
+                );
+            }
+            for chest in dungeon.chests.iter() {
+                assert!(
+                    seen.contains(&(chest.floor, chest.x, chest.y)),
+                    "{}",
+                    dungeon.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_dungeon("floor 1 dark").is_err());
+        assert!(parse_dungeon("dungeon テスト\nfloor 1 dark\n###\n#.\n").is_err());
+        assert!(
+            parse_dungeon("dungeon テスト\nfloor 1 dark\n###\n#.#\n###\nchest 1 1 1 gold 5")
+                .is_err()
+        );
+        assert!(
+            parse_dungeon("dungeon テスト\nfloor 1 dark\n###\n#$#\n###\nchest 1 1 1 item 99")
+                .is_err()
+        );
+        let dungeon =
+            parse_dungeon("dungeon テスト\nfloor 1 lit\n###\n#$#\n###\nchest 1 1 1 key").unwrap();
+        assert_eq!(
+            dungeon.chest_at(1, 1, 1).unwrap().content,
+            ChestContent::Key
+        );
+    }
+
+    #[test]
+    fn test_render_visible() {
+        let dungeons = load_dungeons().unwrap();
+        let floor = dungeons[0].floor(1).unwrap();
+        assert_eq!(floor.render()[1], "#<.#.....#");
+        let view = floor.render_visible(1, 1, Some(1));
+        assert_eq!(view[0], "###       ");
+        assert_eq!(view[1], "#<.       ");
+        assert_eq!(view[3], "          ");
+        assert_eq!(floor.render_visible(1, 1, Some(0))[1], " <        ");
+    }
+}
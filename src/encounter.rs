@@ -0,0 +1,217 @@
+use crate::constants::config::BIT_8_MAX;
+use crate::constants::encounter::{
+    DUNGEON_ENCOUNTER_RATIO, DUNGEON_ENCOUNTERS, GRASS_ENCOUNTER_RATIO, HILLS_ENCOUNTER_RATIO,
+    ROUGH_ENCOUNTER_RATIO, WORLD_ZONE_MAP, ZONE_MONSTERS, ZONE_SIZE,
+};
+use crate::constants::world::Terrain;
+use crate::monster::Monster;
+use crate::player::Player;
+use crate::utility::random_utils::{random_value, success_rate_by_ratio};
+use crate::world::World;
+
+/// (x, y) が属する出現テーブル番号
+pub fn zone_at(x: usize, y: usize) -> Option<u8> {
+    WORLD_ZONE_MAP
+        .get(y / ZONE_SIZE)
+        .and_then(|row| row.get(x / ZONE_SIZE))
+        .copied()
+}
+
+pub fn zone_monsters(zone: u8) -> Option<&'static [u8; 5]> {
+    ZONE_MONSTERS.get(zone as usize)
+}
+
+pub fn dungeon_monsters(name: &str, floor: u8) -> Option<&'static [u8; 5]> {
+    DUNGEON_ENCOUNTERS
+        .iter()
+        .find(|d| d.name == name && d.floor == floor)
+        .map(|d| &d.monsters)
+}
+
+/// 地形ごとの遭遇率の分母（1/n）。町・ほこら などの入口や歩けない地形では遭遇しない
+pub fn encounter_ratio(terrain: Terrain) -> Option<u16> {
+    match terrain {
+        Terrain::Grass | Terrain::Bridge => Some(GRASS_ENCOUNTER_RATIO),
+        Terrain::Desert | Terrain::Forest | Terrain::Swamp | Terrain::Barrier => {
+            Some(ROUGH_ENCOUNTER_RATIO)
+        }
+        Terrain::Hills => Some(HILLS_ENCOUNTER_RATIO),
+        _ => None,
+    }
+}
+
+/// 地上の (x, y) で1歩あたりに遭遇する確率
+pub fn encounter_rate_at(world: &World, x: usize, y: usize) -> f64 {
+    world
+        .tile_at(x, y)
+        .and_then(encounter_ratio)
+        .map_or(0.0, success_rate_by_ratio)
+}
+
+/// トヘロス・せいすい が効く相手か（こうげき力が勇者のしゅび力より低い）
+pub fn is_repelled(monster: &Monster, player: &Player) -> bool {
+    monster.stats.attack < player.defense_power()
+}
+
+fn choose_monster(monsters: &[u8; 5], choice_rand: u8) -> Monster {
+    let index = choice_rand as usize * monsters.len() / 256;
+    Monster::new(monsters[index] as usize)
+}
+
+fn is_encounter(ratio: u16, encounter_rand: u8) -> bool {
+    (encounter_rand as u16) < 256 / ratio
+}
+
+/// 地上を1歩進んだときの遭遇判定
+/// encounter_rand で遭遇するかを、choice_rand で出現テーブルのどれが出るかを決める
+pub fn roll_encounter_with_rand(
+    world: &World,
+    player: &Player,
+    x: usize,
+    y: usize,
+    repel_active: bool,
+    encounter_rand: u8,
+    choice_rand: u8,
+) -> Option<Monster> {
+    let ratio = world.tile_at(x, y).and_then(encounter_ratio)?;
+    if !is_encounter(ratio, encounter_rand) {
+        return None;
+    }
+    let monsters = zone_at(x, y).and_then(zone_monsters)?;
+    let monster = choose_monster(monsters, choice_rand);
+    if repel_active && is_repelled(&monster, player) {
+        return None;
+    }
+    Some(monster)
+}
+
+pub fn roll_encounter(
+    world: &World,
+    player: &Player,
+    x: usize,
+    y: usize,
+    repel_active: bool,
+) -> Option<Monster> {
+    roll_encounter_with_rand(
+        world,
+        player,
+        x,
+        y,
+        repel_active,
+        random_value(BIT_8_MAX),
+        random_value(BIT_8_MAX),
+    )
+}
+
+/// ダンジョンを1歩進んだときの遭遇判定（トヘロス・せいすい は効かない）
+pub fn roll_dungeon_encounter_with_rand(
+    name: &str,
+    floor: u8,
+    encounter_rand: u8,
+    choice_rand: u8,
+) -> Option<Monster> {
+    let monsters = dungeon_monsters(name, floor)?;
+    if !is_encounter(DUNGEON_ENCOUNTER_RATIO, encounter_rand) {
+        return None;
+    }
+    Some(choose_monster(monsters, choice_rand))
+}
+
+pub fn roll_dungeon_encounter(name: &str, floor: u8) -> Option<Monster> {
+    roll_dungeon_encounter_with_rand(
+        name,
+        floor,
+        random_value(BIT_8_MAX),
+        random_value(BIT_8_MAX),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::monster::MONSTER_MASTER;
+    use crate::constants::world::WORLD_ENTRANCES;
+    use crate::player::PlayerArgs;
+
+    #[test]
+    fn test_tables_refer_to_wandering_monsters() {
+        // りゅうおう・ゴーレム・ドラゴン などの固定敵は出現テーブルに含めない
+        let fixed = [32, 38, 39];
+        let all = ZONE_MONSTERS
+            .iter()
+            .chain(DUNGEON_ENCOUNTERS.iter().map(|d| &d.monsters));
+        for monsters in all {
+            for &id in monsters {
+                assert!((id as usize) < MONSTER_MASTER.len());
+                assert!(!fixed.contains(&id), "{}", id);
+            }
+        }
+        for dungeon in DUNGEON_ENCOUNTERS.iter() {
+            assert!(
+                WORLD_ENTRANCES
+                    .iter()
+                    .any(|e| e.name.starts_with(dungeon.name)),
+                "{}",
+                dungeon.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_zone_at() {
+        assert_eq!(zone_at(43, 43), Some(0));
+        assert_eq!(zone_at(0, 0), Some(3));
+        assert_eq!(zone_at(119, 119), Some(13));
+        assert_eq!(zone_at(120, 0), None);
+        assert_eq!(zone_at(0, 120), None);
+    }
+
+    #[test]
+    fn test_roll_encounter_depends_on_terrain() {
+        let world = World::new();
+        let player = Player::new("ゆうてい");
+        // ラダトームのしろ の上では遭遇しない
+        assert!(roll_encounter_with_rand(&world, &player, 43, 43, false, 0, 0).is_none());
+
+        let (x, y) = (44, 43);
+        let ratio = world.tile_at(x, y).and_then(encounter_ratio).unwrap();
+        let hit = (256 / ratio - 1) as u8;
+        let monster = roll_encounter_with_rand(&world, &player, x, y, false, hit, 0).unwrap();
+        assert_eq!(monster.id, ZONE_MONSTERS[0][0]);
+        assert!(roll_encounter_with_rand(&world, &player, x, y, false, hit + 1, 0).is_none());
+        assert_eq!(
+            roll_encounter_with_rand(&world, &player, x, y, false, 0, 255)
+                .unwrap()
+                .id,
+            ZONE_MONSTERS[0][4]
+        );
+        assert!(encounter_rate_at(&world, x, y) > 0.0);
+        assert_eq!(encounter_rate_at(&world, 43, 43), 0.0);
+    }
+
+    #[test]
+    fn test_repel_compares_attack_and_defense() {
+        let world = World::new();
+        let weak = Player::new("ゆうてい");
+        let strong = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(10),
+            armor: Some(3),
+            ..Default::default()
+        });
+        let slime = Monster::new(0);
+        assert!(!is_repelled(&slime, &weak));
+        assert!(is_repelled(&slime, &strong));
+
+        assert!(roll_encounter_with_rand(&world, &strong, 44, 43, true, 0, 0).is_none());
+        assert!(roll_encounter_with_rand(&world, &strong, 44, 43, false, 0, 0).is_some());
+    }
+
+    #[test]
+    fn test_roll_dungeon_encounter() {
+        let monster = roll_dungeon_encounter_with_rand("りゅうおうのしろ", 4, 0, 255).unwrap();
+        assert_eq!(monster.id, 37);
+        assert!(roll_dungeon_encounter_with_rand("りゅうおうのしろ", 4, 255, 0).is_none());
+        assert!(roll_dungeon_encounter_with_rand("ラダトームのしろ", 1, 0, 0).is_none());
+    }
+}
@@ -0,0 +1,408 @@
+use crate::constants::item_weapon::{ARMOR_MASTER, Equipment, SHIELD_MASTER, WEAPON_MASTER};
+use crate::constants::town::TOWN_MASTER;
+use crate::constants::world::Terrain;
+use crate::player::Player;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// 調べる ダメージの基準値（2/3 になるかどうかだけを見る）
+const DAMAGE_PROBE: u8 = 255;
+/// 何歩ごとに HP が回復するかを調べる上限
+const REGEN_PROBE_STEPS: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EquipSlot {
+    Weapon,
+    Armor,
+    Shield,
+}
+
+impl EquipSlot {
+    pub fn master(&self) -> &'static [Equipment] {
+        match self {
+            EquipSlot::Weapon => &WEAPON_MASTER,
+            EquipSlot::Armor => &ARMOR_MASTER,
+            EquipSlot::Shield => &SHIELD_MASTER,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EquipSlot::Weapon => "ぶき",
+            EquipSlot::Armor => "よろい",
+            EquipSlot::Shield => "たて",
+        }
+    }
+
+    /// どこかの まちの店で 売っているか
+    pub fn is_sold(&self, id: u8) -> bool {
+        TOWN_MASTER.iter().any(|town| {
+            match self {
+                EquipSlot::Weapon => town.weapons,
+                EquipSlot::Armor => town.armors,
+                EquipSlot::Shield => town.shields,
+            }
+            .contains(&id)
+        })
+    }
+
+    pub fn current(&self, player: &Player) -> u8 {
+        match self {
+            EquipSlot::Weapon => player.weapon,
+            EquipSlot::Armor => player.armor,
+            EquipSlot::Shield => player.shield,
+        }
+    }
+
+    fn set(&self, player: &mut Player, id: u8) {
+        match self {
+            EquipSlot::Weapon => player.weapon = id,
+            EquipSlot::Armor => player.armor = id,
+            EquipSlot::Shield => player.shield = id,
+        }
+    }
+}
+
+impl FromStr for EquipSlot {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "weapon" | "ぶき" => Ok(EquipSlot::Weapon),
+            "armor" | "よろい" => Ok(EquipSlot::Armor),
+            "shield" | "たて" => Ok(EquipSlot::Shield),
+            _ => Err(format!("不明な そうび です: {}", input)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EquipError {
+    InvalidId { slot: EquipSlot, id: u8 },
+    AlreadyEquipped,
+    NothingEquipped,
+}
+
+impl fmt::Display for EquipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquipError::InvalidId { slot, id } => {
+                write!(f, "{}に そのID（{}）は ない", slot.label(), id)
+            }
+            EquipError::AlreadyEquipped => write!(f, "すでに そうびしている"),
+            EquipError::NothingEquipped => write!(f, "なにも そうびしていない"),
+        }
+    }
+}
+
+impl std::error::Error for EquipError {}
+
+/// 原作の店の画面に出る 上がる・下がる の矢印
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatArrow {
+    Up,
+    Down,
+    Same,
+}
+
+impl StatArrow {
+    pub fn between(before: u8, after: u8) -> Self {
+        match after.cmp(&before) {
+            std::cmp::Ordering::Greater => StatArrow::Up,
+            std::cmp::Ordering::Less => StatArrow::Down,
+            std::cmp::Ordering::Equal => StatArrow::Same,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            StatArrow::Up => "↑",
+            StatArrow::Down => "↓",
+            StatArrow::Same => "",
+        }
+    }
+}
+
+/// そうび で決まる つよさ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquipStats {
+    pub weapon: String,
+    pub armor: String,
+    pub shield: String,
+    pub attack_power: u8,
+    pub defense_power: u8,
+    pub spell_damage_reduced: bool,   // じゅもん の ダメージが 2/3
+    pub fire_damage_reduced: bool,    // ほのお の ダメージが 2/3
+    pub terrain_damage_blocked: bool, // どくのぬまち・バリア の ダメージを受けない
+    pub regen_steps: Option<u32>,     // 何歩ごとに HP が回復するか
+}
+
+impl EquipStats {
+    pub fn of(player: &Player) -> Self {
+        Self {
+            weapon: player.get_weapon().name.to_string(),
+            armor: player.get_armor().name.to_string(),
+            shield: player.get_shield().name.to_string(),
+            attack_power: player.attack_power(),
+            defense_power: player.defense_power(),
+            spell_damage_reduced: player.reduce_spell_damage(DAMAGE_PROBE) < DAMAGE_PROBE,
+            fire_damage_reduced: player.reduce_fire_damage(DAMAGE_PROBE) < DAMAGE_PROBE,
+            terrain_damage_blocked: player.terrain_damage(Terrain::Swamp) == 0,
+            regen_steps: (1..=REGEN_PROBE_STEPS).find(|&step| player.armor_regen(step) > 0),
+        }
+    }
+}
+
+/// そうび を変える前と後の くらべ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquipComparison {
+    pub before: EquipStats,
+    pub after: EquipStats,
+    pub attack_arrow: StatArrow,
+    pub defense_arrow: StatArrow,
+}
+
+impl EquipComparison {
+    fn new(before: EquipStats, after: EquipStats) -> Self {
+        Self {
+            attack_arrow: StatArrow::between(before.attack_power, after.attack_power),
+            defense_arrow: StatArrow::between(before.defense_power, after.defense_power),
+            before,
+            after,
+        }
+    }
+
+    pub fn message_lines(&self) -> Vec<String> {
+        let (before, after) = (&self.before, &self.after);
+        let mut lines = vec![
+            format!(
+                "そうび: {} {} {} → {} {} {}",
+                before.weapon, before.armor, before.shield, after.weapon, after.armor, after.shield
+            ),
+            format!(
+                "こうげきりょく: {} → {}{}",
+                before.attack_power,
+                after.attack_power,
+                self.attack_arrow.symbol()
+            ),
+            format!(
+                "しゅびりょく: {} → {}{}",
+                before.defense_power,
+                after.defense_power,
+                self.defense_arrow.symbol()
+            ),
+        ];
+        let effects = [
+            (
+                "じゅもん の ダメージ 2/3",
+                before.spell_damage_reduced,
+                after.spell_damage_reduced,
+            ),
+            (
+                "ほのお の ダメージ 2/3",
+                before.fire_damage_reduced,
+                after.fire_damage_reduced,
+            ),
+            (
+                "ぬまち・バリア を ふせぐ",
+                before.terrain_damage_blocked,
+                after.terrain_damage_blocked,
+            ),
+        ];
+        for (label, was, now) in effects {
+            if was != now {
+                lines.push(format!("{}: {}", label, if now { "あり" } else { "なし" }));
+            }
+        }
+        if before.regen_steps != after.regen_steps {
+            lines.push(match after.regen_steps {
+                Some(steps) => format!("HP かいふく: {}ほ ごとに 1", steps),
+                None => "HP かいふく: なし".to_string(),
+            });
+        }
+        lines
+    }
+}
+
+fn validate(slot: EquipSlot, id: u8) -> Result<&'static Equipment, EquipError> {
+    slot.master()
+        .get(id as usize)
+        .ok_or(EquipError::InvalidId { slot, id })
+}
+
+/// そうび したら どうなるか（player は変えない）
+pub fn compare_equip(
+    player: &Player,
+    slot: EquipSlot,
+    id: u8,
+) -> Result<EquipComparison, EquipError> {
+    validate(slot, id)?;
+    let mut after = player.clone();
+    slot.set(&mut after, id);
+    Ok(EquipComparison::new(
+        EquipStats::of(player),
+        EquipStats::of(&after),
+    ))
+}
+
+/// そうび する。ID は WEAPON_MASTER・ARMOR_MASTER・SHIELD_MASTER の index
+pub fn equip(player: &mut Player, slot: EquipSlot, id: u8) -> Result<EquipComparison, EquipError> {
+    let comparison = compare_equip(player, slot, id)?;
+    if slot.current(player) == id {
+        return Err(EquipError::AlreadyEquipped);
+    }
+    slot.set(player, id);
+    Ok(comparison)
+}
+
+/// そうび を はずす
+pub fn unequip(player: &mut Player, slot: EquipSlot) -> Result<EquipComparison, EquipError> {
+    if slot.current(player) == 0 {
+        return Err(EquipError::NothingEquipped);
+    }
+    equip(player, slot, 0)
+}
+
+/// 予算内で いちばん つよくなる そうび
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BestSetup {
+    pub weapon: u8,
+    pub armor: u8,
+    pub shield: u8,
+    pub cost: u16, // 下取りを引いた 支払い
+    pub comparison: EquipComparison,
+}
+
+impl BestSetup {
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("ひつようなゴールド: {}G", self.cost)];
+        lines.extend(self.comparison.message_lines());
+        lines
+    }
+}
+
+/// 店で売っている そうび（と いまの そうび）から、budget ゴールドで こうげきりょく＋しゅびりょく が
+/// いちばん高くなる組み合わせを探す。買いかえる そうび は 原作の店と同じく 売値で下取りする
+pub fn best_affordable(player: &Player, budget: u16) -> BestSetup {
+    let candidates = |slot: EquipSlot| -> Vec<u8> {
+        let current = slot.current(player);
+        (0..slot.master().len() as u8)
+            .filter(|&id| id == current || slot.is_sold(id))
+            .collect()
+    };
+    let slot_cost = |slot: EquipSlot, id: u8| -> i32 {
+        let current = slot.current(player);
+        if id == current {
+            return 0;
+        }
+        slot.master()[id as usize].price as i32 - slot.master()[current as usize].sell as i32
+    };
+
+    let mut best: Option<(u16, i32, (u8, u8, u8))> = None;
+    let mut trial = player.clone();
+    for weapon in candidates(EquipSlot::Weapon) {
+        for armor in candidates(EquipSlot::Armor) {
+            for shield in candidates(EquipSlot::Shield) {
+                let cost = slot_cost(EquipSlot::Weapon, weapon)
+                    + slot_cost(EquipSlot::Armor, armor)
+                    + slot_cost(EquipSlot::Shield, shield);
+                if cost > budget as i32 {
+                    continue;
+                }
+                (trial.weapon, trial.armor, trial.shield) = (weapon, armor, shield);
+                let score = trial.attack_power() as u16 + trial.defense_power() as u16;
+                // 同じ つよさ なら 安いほう
+                if best.is_none_or(|(s, c, _)| score > s || (score == s && cost < c)) {
+                    best = Some((score, cost, (weapon, armor, shield)));
+                }
+            }
+        }
+    }
+
+    let (_, cost, (weapon, armor, shield)) =
+        best.unwrap_or((0, 0, (player.weapon, player.armor, player.shield)));
+    (trial.weapon, trial.armor, trial.shield) = (weapon, armor, shield);
+    BestSetup {
+        weapon,
+        armor,
+        shield,
+        cost: cost.max(0) as u16,
+        comparison: EquipComparison::new(EquipStats::of(player), EquipStats::of(&trial)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerArgs;
+
+    fn player_at(level: u8) -> Player {
+        Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(level),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_equip_validates_and_compares() {
+        let mut player = player_at(8);
+        assert_eq!(
+            equip(&mut player, EquipSlot::Weapon, 8),
+            Err(EquipError::InvalidId {
+                slot: EquipSlot::Weapon,
+                id: 8
+            })
+        );
+        let comparison = equip(&mut player, EquipSlot::Armor, 7).unwrap();
+        assert_eq!(comparison.defense_arrow, StatArrow::Up);
+        assert_eq!(comparison.attack_arrow, StatArrow::Same);
+        assert!(comparison.after.spell_damage_reduced && comparison.after.fire_damage_reduced);
+        assert!(comparison.after.terrain_damage_blocked);
+        assert_eq!(comparison.after.regen_steps, Some(1));
+        assert_eq!(player.armor, 7);
+        assert_eq!(
+            equip(&mut player, EquipSlot::Armor, 7),
+            Err(EquipError::AlreadyEquipped)
+        );
+
+        let magic = compare_equip(&player, EquipSlot::Armor, 6).unwrap();
+        assert_eq!(magic.defense_arrow, StatArrow::Down);
+        assert_eq!(magic.after.regen_steps, Some(4));
+        assert!(!magic.after.fire_damage_reduced);
+        assert_eq!(player.armor, 7);
+
+        unequip(&mut player, EquipSlot::Armor).unwrap();
+        assert_eq!(
+            unequip(&mut player, EquipSlot::Armor),
+            Err(EquipError::NothingEquipped)
+        );
+    }
+
+    #[test]
+    fn test_best_affordable_stays_in_budget() {
+        let player = player_at(8);
+        let best = best_affordable(&player, 500);
+        assert!(best.cost <= 500);
+        assert_ne!(best.comparison.defense_arrow, StatArrow::Down);
+        assert_ne!(best.comparison.attack_arrow, StatArrow::Down);
+        assert!(best.cost > 0);
+        // ロトの そうび は 店で売っていないので 選ばれない
+        let rich_setup = best_affordable(&player, u16::MAX);
+        assert!(EquipSlot::Weapon.is_sold(rich_setup.weapon));
+        assert!(EquipSlot::Armor.is_sold(rich_setup.armor));
+        let rich = rich_setup.comparison.after;
+        let after = &best.comparison.after;
+        assert!(
+            rich.attack_power as u16 + rich.defense_power as u16
+                >= after.attack_power as u16 + after.defense_power as u16
+        );
+
+        let none = best_affordable(&player, 0);
+        assert_eq!(
+            (none.weapon, none.armor, none.shield, none.cost),
+            (0, 0, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,1203 @@
+use crate::battle::Battle;
+use crate::constants::config::BIT_8_MAX;
+use crate::constants::dungeon::DungeonTile;
+use crate::constants::field::LIGHT_DECAY_STEPS;
+use crate::constants::item_weapon::{ERDRICK_TOKEN_ID, ITEM_MASTER, RAINBOW_DROP_ID};
+use crate::constants::spell::Spell;
+use crate::constants::text::DEFAULT_NAME;
+use crate::constants::world::{ERDRICK_TOKEN_POSITION, TANTEGEL_CASTLE, Terrain};
+use crate::death::{DeathOptions, Revival, apply_death_penalty};
+use crate::dungeon::{Dungeon, Floor, StairsTarget, find_dungeon_entry, load_dungeons};
+use crate::encounter::{roll_dungeon_encounter_with_rand, roll_encounter_with_rand};
+use crate::field_item::{FieldItemError, FieldItemResult, use_field_item};
+use crate::field_spell::{FieldSpellError, FieldSpellResult, cast_field_spell};
+use crate::gauntlet::StageOutcome;
+use crate::inventory::add_item;
+use crate::monster::Monster;
+use crate::player::Player;
+use crate::quest::{QuestError, craft_rainbow_drop};
+use crate::traits::message_output::MessageOutput;
+use crate::traits::player_input::PlayerInput;
+use crate::utility::random_utils::random_value;
+use crate::world::World;
+use crate::world_state::{ChestTake, MapPosition, WorldState, take_chest_content};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// ラダトームのしろ から出たところ
+pub const START_POSITION: (usize, usize) = (43, 44);
+/// にじのしずく を授けてくれる賢者のいる ほこら
+pub const RAINBOW_DROP_SHRINE: &str = "せいなるほこら";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+impl Direction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::North => "きた",
+            Direction::South => "みなみ",
+            Direction::East => "ひがし",
+            Direction::West => "にし",
+        }
+    }
+
+    /// (x, y) から1歩進んだ座標（マップの端より外は None）
+    pub fn step_from(&self, x: usize, y: usize) -> Option<(usize, usize)> {
+        match self {
+            Direction::North => y.checked_sub(1).map(|y| (x, y)),
+            Direction::South => Some((x, y + 1)),
+            Direction::East => Some((x + 1, y)),
+            Direction::West => x.checked_sub(1).map(|x| (x, y)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExploreCommand {
+    Move(Direction),
+    Search,
+    Talk,
+    Stairs,
+    Door,
+    Spell(Spell),
+    Item(u8), // ITEM_MASTER の index
+}
+
+impl FromStr for ExploreCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "n" | "north" | "up" => Ok(Self::Move(Direction::North)),
+            "s" | "south" | "down" => Ok(Self::Move(Direction::South)),
+            "e" | "east" | "right" => Ok(Self::Move(Direction::East)),
+            "w" | "west" | "left" => Ok(Self::Move(Direction::West)),
+            "search" | "しらべる" | "take" | "とる" => Ok(Self::Search),
+            "talk" | "はなす" => Ok(Self::Talk),
+            "stairs" | "かいだん" => Ok(Self::Stairs),
+            "door" | "とびら" => Ok(Self::Door),
+            _ => {
+                if let Ok(spell) = Spell::from_str(s) {
+                    return Ok(Self::Spell(spell));
+                }
+                ITEM_MASTER
+                    .iter()
+                    .skip(1)
+                    .position(|item| item.name == s)
+                    .map(|i| Self::Item(i as u8 + 1))
+                    .ok_or_else(|| format!("Invalid explore command: {}", s))
+            }
+        }
+    }
+}
+
+/// 1コマンドで起きた出来事
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExploreEvent {
+    Moved {
+        x: usize,
+        y: usize,
+        terrain: String,
+    },
+    Blocked {
+        x: usize,
+        y: usize,
+    },
+    TerrainDamage {
+        terrain: String,
+        damage: u8,
+        hp: u8,
+    },
+    HpRegenerated {
+        amount: u8,
+        hp: u8,
+    },
+    EncounterTriggered {
+        monster_id: u8,
+        monster_name: String,
+    },
+    BattleFinished {
+        outcome: StageOutcome,
+    },
+    TownEntered {
+        name: String,
+    },
+    CaveEntered {
+        name: String,
+    },
+    ShrineEntered {
+        name: String,
+    },
+    DungeonEntered {
+        name: String,
+        floor: u8,
+    },
+    FloorChanged {
+        name: String,
+        floor: u8,
+    },
+    DungeonExited {
+        name: String,
+    },
+    TreasureFound {
+        item: String,
+    },
+    InventoryFull {
+        item: String,
+    },
+    ChestEmpty,
+    NothingFound,
+    NoOneThere,
+    NoStairs,
+    DoorOpened {
+        keys: u8,
+    },
+    NoKey,
+    NoDoor,
+    SpellCast(FieldSpellResult),
+    SpellFailed(FieldSpellError),
+    ItemUsed(FieldItemResult),
+    ItemFailed(FieldItemError),
+    QuestFailed(QuestError),
+    PlayerDied,
+    Revived(Revival),
+    EntryRefused {
+        name: String,
+    },
+}
+
+impl ExploreEvent {
+    pub fn message(&self) -> String {
+        match self {
+            ExploreEvent::Moved { x, y, terrain } => format!("({}, {}) {}", x, y, terrain),
+            ExploreEvent::Blocked { x, y } => format!("({}, {}) には すすめない", x, y),
+            ExploreEvent::TerrainDamage {
+                terrain,
+                damage,
+                hp,
+            } => {
+                format!("{}で {}ポイントの ダメージ！ (HP: {})", terrain, damage, hp)
+            }
+            ExploreEvent::HpRegenerated { amount, hp } => {
+                format!(
+                    "よろいの ちからで HPが {} かいふくした (HP: {})",
+                    amount, hp
+                )
+            }
+            ExploreEvent::EncounterTriggered { monster_name, .. } => {
+                format!("{}が あらわれた！", monster_name)
+            }
+            ExploreEvent::BattleFinished { outcome } => match outcome {
+                StageOutcome::Defeated => "モンスターを たおした".to_string(),
+                StageOutcome::PlayerEscaped => "うまく にげきった".to_string(),
+                StageOutcome::MonsterEscaped => "モンスターは にげていった".to_string(),
+                StageOutcome::PlayerDied => "ゆうしゃは しんでしまった".to_string(),
+            },
+            ExploreEvent::TownEntered { name } => format!("{}に はいった", name),
+            ExploreEvent::CaveEntered { name } => format!("{}の いりぐちだ", name),
+            ExploreEvent::ShrineEntered { name } => format!("{}に はいった", name),
+            ExploreEvent::DungeonEntered { name, floor } => {
+                format!("{}の {}かいに はいった", name, floor)
+            }
+            ExploreEvent::FloorChanged { name, floor } => {
+                format!("かいだんを つかって {}の {}かいへ", name, floor)
+            }
+            ExploreEvent::DungeonExited { name } => format!("{}から そとへ でた", name),
+            ExploreEvent::TreasureFound { item } => format!("{}を みつけた！", item),
+            ExploreEvent::InventoryFull { item } => {
+                format!("{}を みつけたが もちものが いっぱいだ", item)
+            }
+            ExploreEvent::ChestEmpty => "たからばこは からっぽだった".to_string(),
+            ExploreEvent::NothingFound => {
+                "あしもとを しらべたが なにも みつからなかった".to_string()
+            }
+            ExploreEvent::NoOneThere => "そのほうこうには だれも いない".to_string(),
+            ExploreEvent::NoStairs => "ここには かいだんが ない".to_string(),
+            ExploreEvent::DoorOpened { keys } => {
+                format!("かぎを つかって とびらを ひらいた (のこり {}こ)", keys)
+            }
+            ExploreEvent::NoKey => "とびらを ひらく かぎを もっていない".to_string(),
+            ExploreEvent::NoDoor => "そのほうこうには とびらが ない".to_string(),
+            ExploreEvent::SpellCast(result) => result.message_lines().join(" "),
+            ExploreEvent::SpellFailed(error) => error.to_string(),
+            ExploreEvent::ItemUsed(result) => result.message_lines().join(" "),
+            ExploreEvent::ItemFailed(error) => error.to_string(),
+            ExploreEvent::QuestFailed(error) => error.to_string(),
+            ExploreEvent::PlayerDied => "ゆうしゃは しんでしまった".to_string(),
+            ExploreEvent::Revived(revival) => revival.message_lines().join(" "),
+            ExploreEvent::EntryRefused { name } => {
+                format!("のろわれし ものを {}に いれるわけには いかぬ！", name)
+            }
+        }
+    }
+}
+
+/// 道のりを歩いたときの HP の変化（戦闘は考えない）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalkReport {
+    pub steps: u32,
+    pub hp_start: u8,
+    pub hp_end: u8,
+    pub damage: u32,
+    pub healed: u32,
+    pub died: bool,
+    pub blocked: Option<(usize, usize)>, // 進めなかったマス（そこで止まる）
+}
+
+impl WalkReport {
+    pub fn hp_delta(&self) -> i16 {
+        self.hp_end as i16 - self.hp_start as i16
+    }
+}
+
+/// start から地上を directions の順に歩いたときの 地形ダメージ・よろい の回復 を調べる
+pub fn simulate_walk(
+    player: &Player,
+    world: &World,
+    start: (usize, usize),
+    directions: &[Direction],
+) -> WalkReport {
+    let mut report = WalkReport {
+        steps: 0,
+        hp_start: player.hp,
+        hp_end: player.hp,
+        damage: 0,
+        healed: 0,
+        died: false,
+        blocked: None,
+    };
+    let (mut x, mut y) = start;
+    for direction in directions {
+        let Some((nx, ny)) = direction
+            .step_from(x, y)
+            .filter(|&(nx, ny)| world.is_walkable(nx, ny))
+        else {
+            report.blocked = Some((x, y));
+            break;
+        };
+        (x, y) = (nx, ny);
+        report.steps += 1;
+        let (hp, effect) = player.step_hp(report.hp_end, world.tile_at(x, y), report.steps);
+        report.hp_end = hp;
+        report.damage += effect.damage as u32;
+        report.healed += effect.healed as u32;
+        if hp == 0 {
+            report.died = true;
+            break;
+        }
+    }
+    report
+}
+
+/// フィールド探索のセッション。入出力は呼び出し側から渡すので CLI・WASM のどちらからも動かせる
+#[derive(Debug)]
+pub struct Exploration {
+    pub player: Player,
+    pub world: World,
+    pub dungeons: Vec<Dungeon>,
+    pub world_state: WorldState, // あけた たからばこ・ひらいた とびら
+    pub x: usize,
+    pub y: usize,
+    pub facing: Direction,
+    pub repel_steps: u16, // トヘロス・せいすい の残り歩数
+    pub steps: u32,
+    pub step_damage: u32,                 // 地形から受けた ダメージの合計
+    pub step_healed: u32,                 // よろい で回復した HP の合計
+    pub dungeon: Option<DungeonLocation>, // None なら地上（ダンジョンの中では x, y は階の座標）
+    pub light_radius: u8,                 // ダンジョンの明かり（0 は真っ暗）
+    pub light_steps: u16,                 // 明かりが小さくなるまでの歩数（0 なら小さくならない）
+    pub revive: Option<DeathOptions>, // しんだら ラダトームで生き返る（None なら そこで おわり）
+}
+
+/// いまいるダンジョン
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DungeonLocation {
+    pub name: String,
+    pub floor: u8,
+    pub exit: (usize, usize), // 地上の入口
+}
+
+impl Exploration {
+    pub fn new(player: Player) -> Self {
+        let (x, y) = START_POSITION;
+        Self::new_at(player, x, y)
+    }
+
+    pub fn new_at(player: Player, x: usize, y: usize) -> Self {
+        Self {
+            player,
+            world: World::new(),
+            dungeons: load_dungeons().unwrap_or_default(),
+            world_state: WorldState::new(),
+            x,
+            y,
+            facing: Direction::South,
+            repel_steps: 0,
+            steps: 0,
+            step_damage: 0,
+            step_healed: 0,
+            dungeon: None,
+            light_radius: 0,
+            light_steps: 0,
+            revive: None,
+        }
+    }
+
+    pub fn is_in_dungeon(&self) -> bool {
+        self.dungeon.is_some()
+    }
+
+    /// 地上の (x, y) に移動する。ダンジョンの明かりは消える
+    pub fn warp_to(&mut self, x: usize, y: usize) {
+        self.x = x;
+        self.y = y;
+        self.dungeon = None;
+        self.light_radius = 0;
+        self.light_steps = 0;
+    }
+
+    /// 明かりを付ける。decay_steps が 0 なら小さくならない
+    pub fn light(&mut self, radius: u8, decay_steps: u16) {
+        self.light_radius = radius;
+        self.light_steps = decay_steps;
+    }
+
+    /// 1歩ごとに明かりを弱める
+    fn tick_light(&mut self) {
+        if self.light_steps == 0 || self.light_radius == 0 {
+            return;
+        }
+        self.light_steps -= 1;
+        if self.light_steps == 0 {
+            self.light_radius -= 1;
+            if self.light_radius > 0 {
+                self.light_steps = LIGHT_DECAY_STEPS;
+            }
+        }
+    }
+
+    /// いまいるダンジョンのデータ
+    pub fn current_dungeon(&self) -> Option<&Dungeon> {
+        let location = self.dungeon.as_ref()?;
+        self.dungeons.iter().find(|d| d.name == location.name)
+    }
+
+    /// いまいる階のマップ
+    pub fn current_floor(&self) -> Option<&Floor> {
+        let floor = self.dungeon.as_ref()?.floor;
+        self.current_dungeon()?.floor(floor)
+    }
+
+    /// いまいる階の (x, y) を表すマス
+    fn map_position(&self, x: usize, y: usize) -> Option<MapPosition> {
+        let location = self.dungeon.as_ref()?;
+        Some(MapPosition::new(&location.name, location.floor, x, y))
+    }
+
+    /// いまいる階のマス。あけた たからばこ・ひらいた とびら は ゆか になる
+    pub fn dungeon_tile_at(&self, x: usize, y: usize) -> Option<DungeonTile> {
+        let tile = self.current_floor()?.tile_at(x, y)?;
+        let position = self.map_position(x, y)?;
+        let changed = match tile {
+            DungeonTile::Chest => self.world_state.is_chest_opened(&position),
+            DungeonTile::Door => self.world_state.is_door_unlocked(&position),
+            _ => false,
+        };
+        Some(if changed { DungeonTile::Floor } else { tile })
+    }
+
+    /// ふっかつのじゅもん を入れ直したときのように たからばこ・とびら を元に戻す
+    pub fn reset_world_state(&mut self) {
+        self.world_state.reset();
+    }
+
+    /// 見えるマスの半径。地上や明るい階では None（すべて見える）
+    pub fn visible_radius(&self) -> Option<u8> {
+        match self.current_floor() {
+            Some(floor) if floor.dark => Some(self.light_radius),
+            _ => None,
+        }
+    }
+
+    /// ダンジョンの中なら 見えている範囲の階のマップ（自分は '@'）
+    pub fn render_view(&self) -> Option<Vec<String>> {
+        let floor = self.current_floor()?;
+        let lines = floor.render_visible(self.x, self.y, self.visible_radius());
+        let lines = lines
+            .iter()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        if (x, y) == self.position() {
+                            '@'
+                        } else if c == ' ' {
+                            c
+                        } else {
+                            self.dungeon_tile_at(x, y).map_or(c, |t| t.to_char())
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Some(lines)
+    }
+
+    pub fn position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    /// 地上の地形（ダンジョンの中では None）
+    pub fn terrain(&self) -> Option<Terrain> {
+        if self.is_in_dungeon() {
+            return None;
+        }
+        self.world.tile_at(self.x, self.y)
+    }
+
+    pub fn into_player(self) -> Player {
+        self.player
+    }
+
+    pub fn execute(
+        &mut self,
+        command: ExploreCommand,
+        input: &mut dyn PlayerInput,
+        output: &mut dyn MessageOutput,
+    ) -> Vec<ExploreEvent> {
+        self.execute_with_rand(
+            command,
+            random_value(BIT_8_MAX),
+            random_value(BIT_8_MAX),
+            input,
+            output,
+        )
+    }
+
+    /// encounter_rand・choice_rand は 移動先での遭遇判定に使う（roll_encounter_with_rand を参照）
+    pub fn execute_with_rand(
+        &mut self,
+        command: ExploreCommand,
+        encounter_rand: u8,
+        choice_rand: u8,
+        input: &mut dyn PlayerInput,
+        output: &mut dyn MessageOutput,
+    ) -> Vec<ExploreEvent> {
+        if !self.player.is_alive() {
+            return vec![ExploreEvent::PlayerDied];
+        }
+        match command {
+            ExploreCommand::Move(direction) => {
+                self.move_to(direction, encounter_rand, choice_rand, input, output)
+            }
+            ExploreCommand::Search => vec![self.search()],
+            ExploreCommand::Talk => vec![self.talk()],
+            ExploreCommand::Stairs => vec![self.use_stairs()],
+            ExploreCommand::Door => vec![self.open_door()],
+            ExploreCommand::Spell(spell) => vec![match cast_field_spell(self, spell) {
+                Ok(result) => ExploreEvent::SpellCast(result),
+                Err(error) => ExploreEvent::SpellFailed(error),
+            }],
+            ExploreCommand::Item(id) => vec![match use_field_item(self, id) {
+                Ok(result) => ExploreEvent::ItemUsed(result),
+                Err(error) => ExploreEvent::ItemFailed(error),
+            }],
+        }
+    }
+
+    fn move_to(
+        &mut self,
+        direction: Direction,
+        encounter_rand: u8,
+        choice_rand: u8,
+        input: &mut dyn PlayerInput,
+        output: &mut dyn MessageOutput,
+    ) -> Vec<ExploreEvent> {
+        self.facing = direction;
+        if self.is_in_dungeon() {
+            return self.move_in_dungeon(direction, encounter_rand, choice_rand, input, output);
+        }
+        let Some((x, y)) = direction
+            .step_from(self.x, self.y)
+            .filter(|&(x, y)| self.world.is_walkable(x, y))
+        else {
+            return vec![ExploreEvent::Blocked {
+                x: self.x,
+                y: self.y,
+            }];
+        };
+        // のろわれていると 城の兵士に追い返される
+        if let Some(entrance) = self.world.entrance_at(x, y)
+            && entrance.name == TANTEGEL_CASTLE
+            && self.player.is_cursed()
+        {
+            return vec![ExploreEvent::EntryRefused {
+                name: entrance.name.to_string(),
+            }];
+        }
+        self.x = x;
+        self.y = y;
+        self.steps += 1;
+        self.repel_steps = self.repel_steps.saturating_sub(1);
+
+        let terrain = self.world.tile_at(x, y).unwrap_or(Terrain::Grass);
+        let mut events = vec![ExploreEvent::Moved {
+            x,
+            y,
+            terrain: terrain.as_str().to_string(),
+        }];
+
+        if let Some(entrance) = self.world.entrance_at(x, y) {
+            if !self.step_effect(Some(terrain), &mut events) {
+                return events;
+            }
+            if let Some(event) = self.enter_dungeon(entrance.name) {
+                events.push(event);
+                return events;
+            }
+            let name = entrance.name.to_string();
+            events.push(match entrance.terrain {
+                Terrain::Cave => ExploreEvent::CaveEntered { name },
+                Terrain::Shrine => ExploreEvent::ShrineEntered { name },
+                _ => ExploreEvent::TownEntered { name },
+            });
+            return events;
+        }
+
+        if !self.step_effect(Some(terrain), &mut events) {
+            return events;
+        }
+
+        let monster = roll_encounter_with_rand(
+            &self.world,
+            &self.player,
+            x,
+            y,
+            self.repel_steps > 0,
+            encounter_rand,
+            choice_rand,
+        );
+        self.encounter(monster, &mut events, input, output);
+        events
+    }
+
+    fn move_in_dungeon(
+        &mut self,
+        direction: Direction,
+        encounter_rand: u8,
+        choice_rand: u8,
+        input: &mut dyn PlayerInput,
+        output: &mut dyn MessageOutput,
+    ) -> Vec<ExploreEvent> {
+        let Some((x, y, tile)) = direction.step_from(self.x, self.y).and_then(|(x, y)| {
+            let tile = self.dungeon_tile_at(x, y).filter(|t| t.is_walkable())?;
+            Some((x, y, tile.as_str().to_string()))
+        }) else {
+            return vec![ExploreEvent::Blocked {
+                x: self.x,
+                y: self.y,
+            }];
+        };
+        self.x = x;
+        self.y = y;
+        self.steps += 1;
+        self.tick_light();
+
+        let mut events = vec![ExploreEvent::Moved {
+            x,
+            y,
+            terrain: tile,
+        }];
+        self.step_effect(None, &mut events);
+        // ダンジョンでは トヘロス・せいすい は効かない
+        let monster = self.dungeon.as_ref().and_then(|location| {
+            roll_dungeon_encounter_with_rand(
+                &location.name,
+                location.floor,
+                encounter_rand,
+                choice_rand,
+            )
+        });
+        self.encounter(monster, &mut events, input, output);
+        events
+    }
+
+    /// 1歩ごとの 地形ダメージ・よろい の回復。しんでしまったら false
+    fn step_effect(&mut self, terrain: Option<Terrain>, events: &mut Vec<ExploreEvent>) -> bool {
+        let effect = self.player.take_step(terrain, self.steps);
+        self.step_damage += effect.damage as u32;
+        self.step_healed += effect.healed as u32;
+        if effect.damage > 0 {
+            events.push(ExploreEvent::TerrainDamage {
+                terrain: terrain.map_or("", |t| t.as_str()).to_string(),
+                damage: effect.damage,
+                hp: self.player.hp,
+            });
+        }
+        if !self.player.is_alive() {
+            self.die(events);
+            return false;
+        }
+        if effect.healed > 0 {
+            events.push(ExploreEvent::HpRegenerated {
+                amount: effect.healed,
+                hp: self.player.hp,
+            });
+        }
+        true
+    }
+
+    fn encounter(
+        &mut self,
+        monster: Option<Monster>,
+        events: &mut Vec<ExploreEvent>,
+        input: &mut dyn PlayerInput,
+        output: &mut dyn MessageOutput,
+    ) {
+        let Some(monster) = monster else {
+            return;
+        };
+        events.push(ExploreEvent::EncounterTriggered {
+            monster_id: monster.id,
+            monster_name: monster.name().to_string(),
+        });
+        let outcome = self.fight(monster, input, output);
+        events.push(ExploreEvent::BattleFinished {
+            outcome: outcome.clone(),
+        });
+        if outcome == StageOutcome::PlayerDied {
+            self.die(events);
+        }
+    }
+
+    /// しんでしまった。revive があれば ゴールドを半分失い ラダトームの王様の前で生き返る
+    fn die(&mut self, events: &mut Vec<ExploreEvent>) {
+        events.push(ExploreEvent::PlayerDied);
+        let Some(options) = &self.revive else {
+            return;
+        };
+        let revival = apply_death_penalty(&mut self.player, options);
+        let (x, y) = START_POSITION;
+        self.warp_to(x, y);
+        self.facing = Direction::South;
+        self.repel_steps = 0;
+        events.push(ExploreEvent::Revived(revival));
+    }
+
+    /// 地上の入口から ダンジョンに入る（マップのない入口なら None）
+    fn enter_dungeon(&mut self, entrance: &str) -> Option<ExploreEvent> {
+        let exit = self.position();
+        let (dungeon, entry) = find_dungeon_entry(&self.dungeons, entrance)?;
+        let name = dungeon.name.clone();
+        let (floor, x, y) = (entry.floor, entry.x, entry.y);
+        self.dungeon = Some(DungeonLocation {
+            name: name.clone(),
+            floor,
+            exit,
+        });
+        self.x = x;
+        self.y = y;
+        self.light_radius = 0;
+        self.light_steps = 0;
+        Some(ExploreEvent::DungeonEntered { name, floor })
+    }
+
+    fn use_stairs(&mut self) -> ExploreEvent {
+        let Some(target) = self.current_dungeon().and_then(|dungeon| {
+            let floor = self.dungeon.as_ref()?.floor;
+            dungeon.stairs_at(floor, self.x, self.y).map(|s| s.target)
+        }) else {
+            return ExploreEvent::NoStairs;
+        };
+        let Some(location) = self.dungeon.as_mut() else {
+            return ExploreEvent::NoStairs;
+        };
+        let name = location.name.clone();
+        match target {
+            StairsTarget::Floor { floor, x, y } => {
+                location.floor = floor;
+                self.x = x;
+                self.y = y;
+                ExploreEvent::FloorChanged { name, floor }
+            }
+            StairsTarget::World { x, y } => {
+                self.warp_to(x, y);
+                ExploreEvent::DungeonExited { name }
+            }
+        }
+    }
+
+    fn fight(
+        &mut self,
+        monster: Monster,
+        input: &mut dyn PlayerInput,
+        output: &mut dyn MessageOutput,
+    ) -> StageOutcome {
+        // Battle はプレイヤーを所有するので、戦闘の間だけ仮のプレイヤーと入れ替える
+        let player = std::mem::replace(&mut self.player, Player::new(DEFAULT_NAME));
+        let mut battle = Battle::new(player, monster, input, output);
+        battle.start();
+        let outcome = StageOutcome::from_battle(&battle);
+        self.player = battle.player;
+        outcome
+    }
+
+    fn talk(&mut self) -> ExploreEvent {
+        let at_shrine = !self.is_in_dungeon()
+            && self
+                .world
+                .entrance_at(self.x, self.y)
+                .is_some_and(|e| e.name == RAINBOW_DROP_SHRINE);
+        if !at_shrine {
+            return ExploreEvent::NoOneThere;
+        }
+        match craft_rainbow_drop(&mut self.player) {
+            Ok(()) => ExploreEvent::TreasureFound {
+                item: ITEM_MASTER[RAINBOW_DROP_ID as usize].name.to_string(),
+            },
+            Err(error) => ExploreEvent::QuestFailed(error),
+        }
+    }
+
+    /// むいている方向の とびら を かぎ で ひらく
+    fn open_door(&mut self) -> ExploreEvent {
+        let Some((x, y)) = self.facing.step_from(self.x, self.y) else {
+            return ExploreEvent::NoDoor;
+        };
+        if self.dungeon_tile_at(x, y) != Some(DungeonTile::Door) {
+            return ExploreEvent::NoDoor;
+        }
+        if self.player.keys == 0 {
+            return ExploreEvent::NoKey;
+        }
+        let Some(position) = self.map_position(x, y) else {
+            return ExploreEvent::NoDoor;
+        };
+        self.player.keys -= 1;
+        self.world_state.unlock_door(position);
+        ExploreEvent::DoorOpened {
+            keys: self.player.keys,
+        }
+    }
+
+    /// あしもとの たからばこ を あける
+    fn open_chest(&mut self) -> ExploreEvent {
+        let Some(position) = self.map_position(self.x, self.y) else {
+            return ExploreEvent::NothingFound;
+        };
+        let chest = self
+            .current_dungeon()
+            .and_then(|dungeon| dungeon.chest_at(position.floor, position.x, position.y));
+        let Some(content) = chest
+            .filter(|_| !self.world_state.is_chest_opened(&position))
+            .map(|chest| chest.content)
+        else {
+            return ExploreEvent::NothingFound;
+        };
+        match take_chest_content(&mut self.player, content) {
+            ChestTake::Received(item) => {
+                self.world_state.open_chest(position);
+                ExploreEvent::TreasureFound { item }
+            }
+            ChestTake::Empty => {
+                self.world_state.open_chest(position);
+                ExploreEvent::ChestEmpty
+            }
+            ChestTake::Full(item) => ExploreEvent::InventoryFull { item },
+        }
+    }
+
+    fn search(&mut self) -> ExploreEvent {
+        if self.is_in_dungeon() {
+            return self.open_chest();
+        }
+        if self.position() != ERDRICK_TOKEN_POSITION
+            || self.player.items.contains(&ERDRICK_TOKEN_ID)
+        {
+            return ExploreEvent::NothingFound;
+        }
+        let item = ITEM_MASTER[ERDRICK_TOKEN_ID as usize].name.to_string();
+        match add_item(&mut self.player, ERDRICK_TOKEN_ID) {
+            Ok(()) => ExploreEvent::TreasureFound { item },
+            Err(_) => ExploreEvent::InventoryFull { item },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::item_weapon::{CURSED_BELT_ID, SILVER_HARP_ID};
+    use crate::constants::world::SWAMP_DAMAGE;
+    use crate::input::auto_input::{AutoInput, AutoPolicy};
+    use crate::output::buffer_output::BufferOutput;
+
+    fn run(exploration: &mut Exploration, command: &str, encounter_rand: u8) -> Vec<ExploreEvent> {
+        let mut input = AutoInput::new(AutoPolicy::attack_only());
+        let mut output = BufferOutput::new();
+        exploration.execute_with_rand(
+            command.parse().unwrap(),
+            encounter_rand,
+            0,
+            &mut input,
+            &mut output,
+        )
+    }
+
+    #[test]
+    fn test_command_from_str() {
+        assert_eq!(
+            ExploreCommand::from_str("N"),
+            Ok(ExploreCommand::Move(Direction::North))
+        );
+        assert_eq!(
+            ExploreCommand::from_str("search"),
+            Ok(ExploreCommand::Search)
+        );
+        assert_eq!(
+            ExploreCommand::from_str("ホイミ"),
+            Ok(ExploreCommand::Spell(Spell::Hoimi))
+        );
+        assert_eq!(
+            ExploreCommand::from_str("せいすい"),
+            Ok(ExploreCommand::Item(2))
+        );
+        assert!(ExploreCommand::from_str("なし").is_err());
+        assert!(ExploreCommand::from_str("jump").is_err());
+    }
+
+    #[test]
+    fn test_move_and_enter_castle() {
+        let mut exploration = Exploration::new(Player::new("ゆうてい"));
+        let events = run(&mut exploration, "n", 255);
+        assert_eq!(exploration.position(), (43, 43));
+        assert_eq!(exploration.facing, Direction::North);
+        assert_eq!(
+            events[1],
+            ExploreEvent::TownEntered {
+                name: "ラダトームのしろ".to_string()
+            }
+        );
+
+        // うみ には進めない
+        let mut exploration = Exploration::new_at(Player::new("ゆうてい"), 1, 1);
+        assert_eq!(
+            run(&mut exploration, "w", 255),
+            vec![ExploreEvent::Blocked { x: 1, y: 1 }]
+        );
+        assert_eq!(exploration.steps, 0);
+    }
+
+    #[test]
+    fn test_light_decays_step_by_step() {
+        let mut exploration = Exploration::new(Player::new("ゆうてい"));
+        exploration.light(2, 2);
+        exploration.tick_light();
+        assert_eq!(exploration.light_radius, 2);
+        exploration.tick_light();
+        assert_eq!(
+            (exploration.light_radius, exploration.light_steps),
+            (1, LIGHT_DECAY_STEPS)
+        );
+        for _ in 0..LIGHT_DECAY_STEPS {
+            exploration.tick_light();
+        }
+        assert_eq!((exploration.light_radius, exploration.light_steps), (0, 0));
+
+        // たいまつ のように小さくならない明かり
+        exploration.light(1, 0);
+        exploration.tick_light();
+        assert_eq!(exploration.light_radius, 1);
+    }
+
+    #[test]
+    fn test_encounter_runs_battle() {
+        let mut player = Player::new("ゆうてい");
+        player.maximize();
+        player.hp = player.max_hp();
+        let mut exploration = Exploration::new(player);
+        let events = run(&mut exploration, "e", 0);
+        assert!(matches!(events[1], ExploreEvent::EncounterTriggered { .. }));
+        assert!(matches!(events[2], ExploreEvent::BattleFinished { .. }));
+        assert_eq!(events.len(), 3);
+        assert!(exploration.player.is_alive());
+    }
+
+    #[test]
+    fn test_talk_to_sage_for_rainbow_drop() {
+        let mut player = Player::new("ゆうてい");
+        player.items = [7, 12, 0, 0, 0, 0, 0, 0];
+        let shrine = World::new().find_entrance(RAINBOW_DROP_SHRINE).unwrap();
+        let mut exploration = Exploration::new_at(player, shrine.x, shrine.y);
+        assert!(matches!(
+            run(&mut exploration, "talk", 255)[0],
+            ExploreEvent::QuestFailed(QuestError::MissingItems(_))
+        ));
+        exploration.player.items[2] = 13;
+        assert_eq!(
+            run(&mut exploration, "talk", 255),
+            vec![ExploreEvent::TreasureFound {
+                item: "にじのしずく".to_string()
+            }]
+        );
+        assert!(exploration.player.items.contains(&RAINBOW_DROP_ID));
+    }
+
+    #[test]
+    fn test_swamp_damage_and_treasure() {
+        let (x, y) = ERDRICK_TOKEN_POSITION;
+        let mut exploration = Exploration::new_at(Player::new("ゆうてい"), x - 1, y);
+        let hp = exploration.player.hp;
+        let events = run(&mut exploration, "e", 255);
+        assert_eq!(
+            events[1],
+            ExploreEvent::TerrainDamage {
+                terrain: "どくのぬまち".to_string(),
+                damage: SWAMP_DAMAGE,
+                hp: hp - SWAMP_DAMAGE
+            }
+        );
+        assert_eq!(
+            run(&mut exploration, "search", 255),
+            vec![ExploreEvent::TreasureFound {
+                item: "ロトのしるし".to_string()
+            }]
+        );
+        assert_eq!(
+            run(&mut exploration, "search", 255),
+            vec![ExploreEvent::NothingFound]
+        );
+
+        exploration.player.hp = 1;
+        let events = run(&mut exploration, "w", 255);
+        assert_eq!(events.last(), Some(&ExploreEvent::PlayerDied));
+        assert_eq!(
+            run(&mut exploration, "e", 255),
+            vec![ExploreEvent::PlayerDied]
+        );
+    }
+
+    #[test]
+    fn test_revive_at_castle_after_death() {
+        let (x, y) = ERDRICK_TOKEN_POSITION;
+        let mut exploration = Exploration::new_at(Player::new("ゆうてい"), x - 1, y);
+        exploration.revive = Some(DeathOptions::default());
+        exploration.player.hp = 1;
+        exploration.player.gold = 30;
+        let events = run(&mut exploration, "e", 255);
+        assert_eq!(events[events.len() - 2], ExploreEvent::PlayerDied);
+        assert!(matches!(events.last(), Some(ExploreEvent::Revived(r)) if r.gold_lost == 15));
+        assert_eq!(exploration.position(), START_POSITION);
+        assert_eq!(exploration.player.hp, exploration.player.max_hp());
+        assert_eq!(exploration.player.gold, 15);
+        assert!(matches!(
+            run(&mut exploration, "e", 255)[0],
+            ExploreEvent::Moved { .. }
+        ));
+    }
+
+    #[test]
+    fn test_cursed_player_is_refused_at_castle() {
+        let (x, y) = START_POSITION;
+        let mut player = Player::new("ゆうてい");
+        player.items[0] = CURSED_BELT_ID;
+        player.equip_cursed_belt();
+        let mut exploration = Exploration::new_at(player, x, y);
+        assert_eq!(
+            run(&mut exploration, "n", 255),
+            vec![ExploreEvent::EntryRefused {
+                name: TANTEGEL_CASTLE.to_string()
+            }]
+        );
+        assert_eq!(exploration.position(), START_POSITION);
+
+        exploration.player.lift_curse();
+        run(&mut exploration, "n", 255);
+        assert_ne!(exploration.position(), START_POSITION);
+    }
+
+    #[test]
+    fn test_death_on_entrance_step_does_not_enter_dungeon() {
+        let cave = World::new().find_entrance("ロトのどうくつ").unwrap();
+        let mut exploration = Exploration::new_at(Player::new("ゆうてい"), cave.x, cave.y + 1);
+        // execute は しんでいると動かないので、入口への1歩で しんだ場合を move_to で再現する
+        exploration.player.hp = 0;
+        let mut input = AutoInput::new(AutoPolicy::attack_only());
+        let mut output = BufferOutput::new();
+        let events = exploration.move_to(Direction::North, 255, 0, &mut input, &mut output);
+        assert_eq!(events.last(), Some(&ExploreEvent::PlayerDied));
+        assert!(!exploration.is_in_dungeon());
+        assert!(
+            !events
+                .iter()
+                .any(|event| matches!(event, ExploreEvent::DungeonEntered { .. }))
+        );
+    }
+
+    #[test]
+    fn test_dungeon_stairs_and_darkness() {
+        let cave = World::new().find_entrance("ロトのどうくつ").unwrap();
+        let mut player = Player::new("ゆうてい");
+        player.maximize();
+        player.hp = player.max_hp();
+        let mut exploration = Exploration::new_at(player, cave.x, cave.y + 1);
+        let events = run(&mut exploration, "n", 255);
+        assert_eq!(
+            events[1],
+            ExploreEvent::DungeonEntered {
+                name: "ロトのどうくつ".to_string(),
+                floor: 1
+            }
+        );
+        assert_eq!(exploration.position(), (1, 1));
+        assert_eq!(exploration.terrain(), None);
+
+        // 真っ暗なら足元しか見えない
+        assert_eq!(exploration.visible_radius(), Some(0));
+        let view = exploration.render_view().unwrap();
+        assert_eq!(view[0], "          ");
+        assert_eq!(view[1], " @        ");
+        exploration.light(1, 0);
+        assert_eq!(exploration.render_view().unwrap()[2], "#..       ");
+
+        assert_eq!(
+            run(&mut exploration, "n", 255),
+            vec![ExploreEvent::Blocked { x: 1, y: 1 }]
+        );
+        assert_eq!(
+            run(&mut exploration, "s", 255),
+            vec![ExploreEvent::Moved {
+                x: 1,
+                y: 2,
+                terrain: "ゆか".to_string()
+            }]
+        );
+        assert!(matches!(
+            run(&mut exploration, "s", 0)[1],
+            ExploreEvent::EncounterTriggered { .. }
+        ));
+
+        (exploration.x, exploration.y) = (8, 5);
+        assert_eq!(
+            run(&mut exploration, "stairs", 255),
+            vec![ExploreEvent::FloorChanged {
+                name: "ロトのどうくつ".to_string(),
+                floor: 2
+            }]
+        );
+        assert_eq!(exploration.position(), (1, 1));
+        assert_eq!(
+            run(&mut exploration, "stairs", 255),
+            vec![ExploreEvent::FloorChanged {
+                name: "ロトのどうくつ".to_string(),
+                floor: 1
+            }]
+        );
+        assert_eq!(exploration.position(), (8, 5));
+
+        (exploration.x, exploration.y) = (1, 1);
+        assert_eq!(
+            run(&mut exploration, "stairs", 255),
+            vec![ExploreEvent::DungeonExited {
+                name: "ロトのどうくつ".to_string()
+            }]
+        );
+        assert_eq!(exploration.position(), (cave.x, cave.y));
+        assert!(!exploration.is_in_dungeon());
+        assert_eq!(exploration.visible_radius(), None);
+        assert_eq!(
+            run(&mut exploration, "stairs", 255),
+            vec![ExploreEvent::NoStairs]
+        );
+    }
+
+    #[test]
+    fn test_door_and_chest_state() {
+        let mut exploration = Exploration::new_at(Player::new("ゆうてい"), 5, 3);
+        exploration.dungeon = Some(DungeonLocation {
+            name: "ガライのはか".to_string(),
+            floor: 3,
+            exit: (6, 6),
+        });
+        exploration.light(3, 0);
+        assert_eq!(
+            run(&mut exploration, "w", 255),
+            vec![ExploreEvent::Blocked { x: 5, y: 3 }]
+        );
+        assert_eq!(
+            run(&mut exploration, "door", 255),
+            vec![ExploreEvent::NoKey]
+        );
+        exploration.player.keys = 1;
+        assert_eq!(
+            run(&mut exploration, "door", 255),
+            vec![ExploreEvent::DoorOpened { keys: 0 }]
+        );
+        assert_eq!(exploration.render_view().unwrap()[3], "  ...@... ");
+        for _ in 0..4 {
+            run(&mut exploration, "w", 255);
+        }
+        assert_eq!(exploration.position(), (1, 3));
+        assert_eq!(
+            run(&mut exploration, "take", 255),
+            vec![ExploreEvent::TreasureFound {
+                item: "ぎんのたてごと".to_string()
+            }]
+        );
+        assert_eq!(
+            run(&mut exploration, "search", 255),
+            vec![ExploreEvent::NothingFound]
+        );
+        assert_eq!(exploration.dungeon_tile_at(1, 3), Some(DungeonTile::Floor));
+
+        // じゅもんを入れ直すと元に戻るが、だいじなもの は ふたつ目を もらえない
+        exploration.reset_world_state();
+        assert_eq!(exploration.dungeon_tile_at(4, 3), Some(DungeonTile::Door));
+        assert_eq!(
+            run(&mut exploration, "search", 255),
+            vec![ExploreEvent::ChestEmpty]
+        );
+        assert_eq!(
+            exploration
+                .player
+                .items
+                .iter()
+                .filter(|&&id| id == SILVER_HARP_ID)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_armor_and_simulated_walk() {
+        let (x, y) = ERDRICK_TOKEN_POSITION;
+        let mut player = Player::new("ゆうてい");
+        player.hp = 10;
+        let world = World::new();
+        let route = [Direction::West, Direction::East, Direction::West];
+        let report = simulate_walk(&player, &world, (x, y), &route);
+        assert_eq!((report.steps, report.damage, report.healed), (3, 6, 0));
+        assert_eq!(report.hp_delta(), -6);
+
+        // ロトのよろい なら ぬまち でも回復する
+        player.armor = 7;
+        player.hp = 10;
+        let report = simulate_walk(&player, &world, (x, y), &route);
+        assert_eq!((report.damage, report.healed, report.hp_end), (0, 3, 13));
+
+        let mut exploration = Exploration::new_at(player, x, y);
+        let events = run(&mut exploration, "w", 255);
+        assert_eq!(events[1], ExploreEvent::HpRegenerated { amount: 1, hp: 11 });
+        assert_eq!((exploration.step_damage, exploration.step_healed), (0, 1));
+
+        let report = simulate_walk(&exploration.player, &world, (1, 1), &[Direction::West]);
+        assert_eq!(report.blocked, Some((1, 1)));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::constants::encounter::REPEL_STEPS;
+use crate::constants::field::TORCH_LIGHT_RADIUS;
+use crate::constants::item_weapon::{
+    CHIMERA_WING_ID, CURSED_BELT_ID, CursedItem, DEATH_NECKLACE_ID, DRAGON_SCALE_ID,
+    FAIRY_WATER_ID, ITEM_MASTER, PRINCESS_LOVE_ID, RAINBOW_DROP_ID, TORCH_ID,
+};
+use crate::constants::world::{
+    RAINBOW_BRIDGE_POSITION, RAINBOW_DROP_POSITION, TANTEGEL_CASTLE, Terrain,
+};
+use crate::exploration::{Exploration, START_POSITION};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldItemError {
+    NotOwned,
+    NotInDungeon, // たいまつ
+    InDungeon,    // せいすい・キメラのつばさ・おうじょのあい
+    NoEffect,     // 使っても なにも おこらない（場所ちがいの にじのしずく など）
+    PlayerDead,
+}
+
+impl fmt::Display for FieldItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldItemError::NotOwned => write!(f, "そのどうぐを もっていない"),
+            FieldItemError::NotInDungeon => write!(f, "ここでは つかう ひつようが ない"),
+            FieldItemError::InDungeon => write!(f, "どうくつの なかでは つかえない"),
+            FieldItemError::NoEffect => write!(f, "しかし なにも おこらなかった"),
+            FieldItemError::PlayerDead => write!(f, "ゆうしゃは しんでいる"),
+        }
+    }
+}
+
+impl std::error::Error for FieldItemError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldItemEffect {
+    Lit { radius: u8 },
+    Repelled { steps: u16 },
+    Warped { x: usize, y: usize },
+    DragonScale { defense: u8, already_equipped: bool },
+    CastleDistance { north: i32, west: i32 }, // しろから見た位置（負なら みなみ・ひがし）
+    RainbowBridge { x: usize, y: usize },
+    Cursed { max_hp: u8, already_cursed: bool }, // のろいのベルト・しのくびかざり
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldItemResult {
+    pub item: String,
+    pub consumed: bool,
+    pub effect: FieldItemEffect,
+}
+
+impl FieldItemResult {
+    pub fn message_lines(&self) -> Vec<String> {
+        let effect = match &self.effect {
+            FieldItemEffect::Lit { radius } => {
+                format!("あたりが あかるくなった（はんけい {}）", radius)
+            }
+            FieldItemEffect::Repelled { steps } => {
+                format!("よわい まものが よりつかなくなった（{}ほ）", steps)
+            }
+            FieldItemEffect::Warped { x, y } => format!("とびたった！ ({}, {})", x, y),
+            FieldItemEffect::DragonScale {
+                defense,
+                already_equipped,
+            } => {
+                if *already_equipped {
+                    format!("すでに みにつけている (しゅびりょく: {})", defense)
+                } else {
+                    format!("りゅうのうろこを みにつけた (しゅびりょく: {})", defense)
+                }
+            }
+            FieldItemEffect::CastleDistance { north, west } => {
+                let vertical = if *north >= 0 { "きた" } else { "みなみ" };
+                let horizontal = if *west >= 0 { "にし" } else { "ひがし" };
+                format!(
+                    "おうじょ「ラダトームのしろから {}へ {}、{}へ {}の ところに います」",
+                    vertical,
+                    north.abs(),
+                    horizontal,
+                    west.abs()
+                )
+            }
+            FieldItemEffect::RainbowBridge { x, y } => {
+                format!("にじのはしが かかった！ ({}, {})", x, y)
+            }
+            FieldItemEffect::Cursed {
+                max_hp,
+                already_cursed,
+            } => {
+                if *already_cursed {
+                    format!("{}が からだを しめつけている", self.item)
+                } else {
+                    format!(
+                        "{}が からだを しめつける。のろわれてしまった！ (さいだいHP: {})",
+                        self.item, max_hp
+                    )
+                }
+            }
+        };
+        vec![format!("{}を つかった！", self.item), effect]
+    }
+}
+
+/// フィールドで どうぐ（ITEM_MASTER の index）を使う。使い切るどうぐは items から取り除く
+pub fn use_field_item(
+    exploration: &mut Exploration,
+    id: u8,
+) -> Result<FieldItemResult, FieldItemError> {
+    if !exploration.player.is_alive() {
+        return Err(FieldItemError::PlayerDead);
+    }
+    if id == 0 || !exploration.player.items.contains(&id) {
+        return Err(FieldItemError::NotOwned);
+    }
+    let in_dungeon = exploration.is_in_dungeon();
+
+    let (effect, consumed) = match id {
+        TORCH_ID => {
+            if !in_dungeon {
+                return Err(FieldItemError::NotInDungeon);
+            }
+            exploration.light(TORCH_LIGHT_RADIUS, 0);
+            (
+                FieldItemEffect::Lit {
+                    radius: TORCH_LIGHT_RADIUS,
+                },
+                true,
+            )
+        }
+        FAIRY_WATER_ID => {
+            if in_dungeon {
+                return Err(FieldItemError::InDungeon);
+            }
+            exploration.repel_steps = REPEL_STEPS;
+            (FieldItemEffect::Repelled { steps: REPEL_STEPS }, true)
+        }
+        CHIMERA_WING_ID => {
+            if in_dungeon {
+                return Err(FieldItemError::InDungeon);
+            }
+            let (x, y) = START_POSITION;
+            exploration.warp_to(x, y);
+            (FieldItemEffect::Warped { x, y }, true)
+        }
+        DRAGON_SCALE_ID => {
+            let already_equipped = exploration.player.flags.has_dragon_scale;
+            exploration.player.flags.has_dragon_scale = true;
+            (
+                FieldItemEffect::DragonScale {
+                    defense: exploration.player.defense_power(),
+                    already_equipped,
+                },
+                false,
+            )
+        }
+        PRINCESS_LOVE_ID => {
+            if in_dungeon {
+                return Err(FieldItemError::InDungeon);
+            }
+            // 距離は ラダトームのしろ の入口から測る
+            let castle = exploration
+                .world
+                .find_entrance(TANTEGEL_CASTLE)
+                .ok_or(FieldItemError::NoEffect)?;
+            (
+                FieldItemEffect::CastleDistance {
+                    north: castle.y as i32 - exploration.y as i32,
+                    west: castle.x as i32 - exploration.x as i32,
+                },
+                false,
+            )
+        }
+        RAINBOW_DROP_ID => {
+            let (bx, by) = RAINBOW_BRIDGE_POSITION;
+            if in_dungeon
+                || exploration.position() != RAINBOW_DROP_POSITION
+                || exploration.world.tile_at(bx, by) != Some(Terrain::Water)
+            {
+                return Err(FieldItemError::NoEffect);
+            }
+            exploration.world.set_tile(bx, by, Terrain::Bridge);
+            // はしは ふっかつのじゅもん に残らないので、原作と同じく しずく は なくならない
+            (FieldItemEffect::RainbowBridge { x: bx, y: by }, false)
+        }
+        CURSED_BELT_ID | DEATH_NECKLACE_ID => {
+            let already_cursed = exploration.player.is_stuck_item(id);
+            if let Some(item) = CursedItem::from_id(id) {
+                exploration.player.curse(item);
+            }
+            (
+                FieldItemEffect::Cursed {
+                    max_hp: exploration.player.max_hp(),
+                    already_cursed,
+                },
+                false,
+            )
+        }
+        _ => return Err(FieldItemError::NoEffect),
+    };
+
+    if consumed {
+        exploration.player.remove_item(id);
+    }
+    Ok(FieldItemResult {
+        item: ITEM_MASTER[id as usize].name.to_string(),
+        consumed,
+        effect,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exploration::DungeonLocation;
+    use crate::player::Player;
+
+    fn exploration_with_items(items: [u8; 8]) -> Exploration {
+        let mut player = Player::new("ゆうてい");
+        player.items = items;
+        Exploration::new(player)
+    }
+
+    #[test]
+    fn test_consumables_are_removed() {
+        let mut exploration = exploration_with_items([2, 3, 2, 0, 0, 0, 0, 0]);
+        let result = use_field_item(&mut exploration, FAIRY_WATER_ID).unwrap();
+        assert!(result.consumed);
+        assert_eq!(exploration.repel_steps, REPEL_STEPS);
+        assert_eq!(exploration.player.items, [3, 2, 0, 0, 0, 0, 0, 0]);
+
+        exploration.warp_to(100, 100);
+        use_field_item(&mut exploration, CHIMERA_WING_ID).unwrap();
+        assert_eq!(exploration.position(), START_POSITION);
+        assert_eq!(exploration.player.items, [2, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            use_field_item(&mut exploration, CHIMERA_WING_ID),
+            Err(FieldItemError::NotOwned)
+        );
+    }
+
+    #[test]
+    fn test_torch_needs_dungeon() {
+        let mut exploration = exploration_with_items([1, 2, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            use_field_item(&mut exploration, TORCH_ID),
+            Err(FieldItemError::NotInDungeon)
+        );
+        exploration.dungeon = Some(DungeonLocation {
+            name: "ロトのどうくつ".to_string(),
+            floor: 1,
+            exit: (28, 12),
+        });
+        use_field_item(&mut exploration, TORCH_ID).unwrap();
+        assert_eq!(
+            (exploration.light_radius, exploration.light_steps),
+            (TORCH_LIGHT_RADIUS, 0)
+        );
+        assert_eq!(
+            use_field_item(&mut exploration, FAIRY_WATER_ID),
+            Err(FieldItemError::InDungeon)
+        );
+        assert_eq!(exploration.player.items[0], FAIRY_WATER_ID);
+    }
+
+    #[test]
+    fn test_dragon_scale_and_princess_love_are_kept() {
+        let mut exploration = exploration_with_items([4, 8, 0, 0, 0, 0, 0, 0]);
+        let defense = exploration.player.defense_power();
+        let result = use_field_item(&mut exploration, DRAGON_SCALE_ID).unwrap();
+        assert_eq!(
+            result.effect,
+            FieldItemEffect::DragonScale {
+                defense: defense + 2,
+                already_equipped: false
+            }
+        );
+        assert!(!result.consumed);
+
+        exploration.warp_to(40, 50);
+        let result = use_field_item(&mut exploration, PRINCESS_LOVE_ID).unwrap();
+        assert_eq!(
+            result.effect,
+            FieldItemEffect::CastleDistance { north: -7, west: 3 }
+        );
+        assert_eq!(exploration.player.items, [4, 8, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_rainbow_drop_builds_bridge_at_right_tile() {
+        let mut exploration = exploration_with_items([14, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            use_field_item(&mut exploration, RAINBOW_DROP_ID),
+            Err(FieldItemError::NoEffect)
+        );
+        let (x, y) = RAINBOW_DROP_POSITION;
+        exploration.warp_to(x, y);
+        let result = use_field_item(&mut exploration, RAINBOW_DROP_ID).unwrap();
+        assert!(!result.consumed);
+        let (bx, by) = RAINBOW_BRIDGE_POSITION;
+        assert_eq!(exploration.world.tile_at(bx, by), Some(Terrain::Bridge));
+        assert_eq!(exploration.player.items[0], RAINBOW_DROP_ID);
+    }
+
+    #[test]
+    fn test_death_necklace_curses_and_lowers_max_hp() {
+        let mut exploration = exploration_with_items([11, 0, 0, 0, 0, 0, 0, 0]);
+        let max_hp = exploration.player.max_hp();
+        let result = use_field_item(&mut exploration, DEATH_NECKLACE_ID).unwrap();
+        assert!(!result.consumed);
+        assert!(exploration.player.is_stuck_item(DEATH_NECKLACE_ID));
+        assert_eq!(exploration.player.max_hp(), max_hp - max_hp / 4);
+        assert_eq!(exploration.player.hp, exploration.player.max_hp());
+        assert!(matches!(
+            use_field_item(&mut exploration, DEATH_NECKLACE_ID)
+                .unwrap()
+                .effect,
+            FieldItemEffect::Cursed {
+                already_cursed: true,
+                ..
+            }
+        ));
+    }
+}
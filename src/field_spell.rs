@@ -0,0 +1,274 @@
+use crate::constants::config::BIT_8_MAX;
+use crate::constants::encounter::REPEL_STEPS;
+use crate::constants::field::{LIGHT_DECAY_STEPS, REMIRA_LIGHT_RADIUS};
+use crate::constants::spell::Spell;
+use crate::exploration::{Exploration, START_POSITION};
+use crate::utility::random_utils::random_value;
+use crate::utility::spell_utils::player_spell_effect_with_rand;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldSpellError {
+    NotLearned,
+    NotEnoughMp { cost: u8, mp: u8 },
+    BattleOnly,   // ギラ・ラリホー・マホトーン・ベギラマ
+    NotInDungeon, // レミーラ・リレミト
+    InDungeon,    // ルーラ・トヘロス
+    PlayerDead,
+}
+
+impl fmt::Display for FieldSpellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldSpellError::NotLearned => write!(f, "その じゅもんは まだ つかえない"),
+            FieldSpellError::NotEnoughMp { cost, mp } => {
+                write!(f, "MPが たりない（{} ひつよう / {}）", cost, mp)
+            }
+            FieldSpellError::BattleOnly => write!(f, "その じゅもんは せんとうでしか つかえない"),
+            FieldSpellError::NotInDungeon => write!(f, "ここでは その じゅもんは きかない"),
+            FieldSpellError::InDungeon => write!(f, "どうくつの なかでは つかえない"),
+            FieldSpellError::PlayerDead => write!(f, "ゆうしゃは しんでいる"),
+        }
+    }
+}
+
+impl std::error::Error for FieldSpellError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldSpellEffect {
+    Healed { amount: u8, hp: u8 },
+    Lit { radius: u8 },
+    ExitedDungeon { x: usize, y: usize },
+    Warped { x: usize, y: usize },
+    Repelled { steps: u16 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSpellResult {
+    pub spell: String,
+    pub mp_cost: u8,
+    pub mp: u8, // 唱えた後のMP
+    pub effect: FieldSpellEffect,
+}
+
+impl FieldSpellResult {
+    pub fn message_lines(&self) -> Vec<String> {
+        let effect = match &self.effect {
+            FieldSpellEffect::Healed { amount, hp } => {
+                format!("HPが {}ポイント かいふくした (HP: {})", amount, hp)
+            }
+            FieldSpellEffect::Lit { radius } => {
+                format!("あたりが あかるくなった（はんけい {}）", radius)
+            }
+            FieldSpellEffect::ExitedDungeon { x, y } => {
+                format!("そとに でた ({}, {})", x, y)
+            }
+            FieldSpellEffect::Warped { x, y } => format!("とびたった！ ({}, {})", x, y),
+            FieldSpellEffect::Repelled { steps } => {
+                format!("よわい まものが よりつかなくなった（{}ほ）", steps)
+            }
+        };
+        vec![format!("{}の じゅもんを となえた！", self.spell), effect]
+    }
+}
+
+/// 戦闘以外で じゅもんを唱える。使えない場所ではMPを消費せずにエラーを返す
+pub fn cast_field_spell(
+    exploration: &mut Exploration,
+    spell: Spell,
+) -> Result<FieldSpellResult, FieldSpellError> {
+    cast_field_spell_with_rand(exploration, spell, random_value(BIT_8_MAX))
+}
+
+/// rand_val は ホイミ・ベホイミ の回復量に使う
+pub fn cast_field_spell_with_rand(
+    exploration: &mut Exploration,
+    spell: Spell,
+    rand_val: u8,
+) -> Result<FieldSpellResult, FieldSpellError> {
+    if !exploration.player.is_alive() {
+        return Err(FieldSpellError::PlayerDead);
+    }
+    let spell_info = exploration
+        .player
+        .spell_list()
+        .into_iter()
+        .find(|info| info.spell == spell)
+        .ok_or(FieldSpellError::NotLearned)?;
+
+    match spell {
+        Spell::Gira | Spell::Rarirho | Spell::Mahoton | Spell::Begirama => {
+            return Err(FieldSpellError::BattleOnly);
+        }
+        Spell::Remira | Spell::Riremito if !exploration.is_in_dungeon() => {
+            return Err(FieldSpellError::NotInDungeon);
+        }
+        Spell::Rura | Spell::Toheros if exploration.is_in_dungeon() => {
+            return Err(FieldSpellError::InDungeon);
+        }
+        _ => {}
+    }
+    if exploration.player.mp < spell_info.mp_cost {
+        return Err(FieldSpellError::NotEnoughMp {
+            cost: spell_info.mp_cost,
+            mp: exploration.player.mp,
+        });
+    }
+    exploration.player.consume_mp(spell_info);
+
+    let effect = match spell {
+        Spell::Remira => {
+            exploration.light(REMIRA_LIGHT_RADIUS, LIGHT_DECAY_STEPS);
+            FieldSpellEffect::Lit {
+                radius: REMIRA_LIGHT_RADIUS,
+            }
+        }
+        Spell::Riremito => {
+            let (x, y) = exploration
+                .dungeon
+                .as_ref()
+                .map(|dungeon| dungeon.exit)
+                .unwrap_or(START_POSITION);
+            exploration.warp_to(x, y);
+            FieldSpellEffect::ExitedDungeon { x, y }
+        }
+        Spell::Rura => {
+            // 王様に ふっかつのじゅもん を聞けるのは ラダトームだけなので いつも ラダトームに戻る
+            let (x, y) = START_POSITION;
+            exploration.warp_to(x, y);
+            FieldSpellEffect::Warped { x, y }
+        }
+        Spell::Toheros => {
+            exploration.repel_steps = REPEL_STEPS;
+            FieldSpellEffect::Repelled { steps: REPEL_STEPS }
+        }
+        _ => {
+            let before = exploration.player.hp;
+            let heal = player_spell_effect_with_rand(spell, rand_val);
+            exploration.player.adjust_hp(heal as i16);
+            FieldSpellEffect::Healed {
+                amount: exploration.player.hp - before, // 最大HPを超えた分は数えない
+                hp: exploration.player.hp,
+            }
+        }
+    };
+
+    Ok(FieldSpellResult {
+        spell: spell.as_str().to_string(),
+        mp_cost: spell_info.mp_cost,
+        mp: exploration.player.mp,
+        effect,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exploration::DungeonLocation;
+    use crate::player::{Player, PlayerArgs};
+
+    fn exploration_at_level(level: u8) -> Exploration {
+        Exploration::new(Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(level),
+            ..Default::default()
+        }))
+    }
+
+    fn enter_dungeon(exploration: &mut Exploration) {
+        exploration.dungeon = Some(DungeonLocation {
+            name: "ロトのどうくつ".to_string(),
+            floor: 1,
+            exit: (28, 12),
+        });
+    }
+
+    #[test]
+    fn test_heal_consumes_mp() {
+        let mut exploration = exploration_at_level(3);
+        exploration.player.hp = 1;
+        let mp = exploration.player.mp;
+        let result = cast_field_spell_with_rand(&mut exploration, Spell::Hoimi, 0).unwrap();
+        assert_eq!(
+            result.effect,
+            FieldSpellEffect::Healed { amount: 10, hp: 11 }
+        );
+        assert_eq!(exploration.player.mp, mp - 4);
+        assert_eq!(result.mp, mp - 4);
+
+        exploration.player.mp = 3;
+        assert_eq!(
+            cast_field_spell(&mut exploration, Spell::Hoimi),
+            Err(FieldSpellError::NotEnoughMp { cost: 4, mp: 3 })
+        );
+        assert_eq!(
+            cast_field_spell(&mut exploration, Spell::Behoimi),
+            Err(FieldSpellError::NotLearned)
+        );
+    }
+
+    #[test]
+    fn test_context_errors_keep_mp() {
+        let mut exploration = exploration_at_level(30);
+        let mp = exploration.player.mp;
+        assert_eq!(
+            cast_field_spell(&mut exploration, Spell::Gira),
+            Err(FieldSpellError::BattleOnly)
+        );
+        assert_eq!(
+            cast_field_spell(&mut exploration, Spell::Riremito),
+            Err(FieldSpellError::NotInDungeon)
+        );
+        assert_eq!(
+            cast_field_spell(&mut exploration, Spell::Remira),
+            Err(FieldSpellError::NotInDungeon)
+        );
+        enter_dungeon(&mut exploration);
+        assert_eq!(
+            cast_field_spell(&mut exploration, Spell::Rura),
+            Err(FieldSpellError::InDungeon)
+        );
+        assert_eq!(
+            cast_field_spell(&mut exploration, Spell::Toheros),
+            Err(FieldSpellError::InDungeon)
+        );
+        assert_eq!(exploration.player.mp, mp);
+    }
+
+    #[test]
+    fn test_dungeon_spells() {
+        let mut exploration = exploration_at_level(30);
+        enter_dungeon(&mut exploration);
+        let result = cast_field_spell(&mut exploration, Spell::Remira).unwrap();
+        assert_eq!(result.effect, FieldSpellEffect::Lit { radius: 3 });
+        assert_eq!(exploration.light_radius, REMIRA_LIGHT_RADIUS);
+
+        let result = cast_field_spell(&mut exploration, Spell::Riremito).unwrap();
+        assert_eq!(
+            result.effect,
+            FieldSpellEffect::ExitedDungeon { x: 28, y: 12 }
+        );
+        assert!(!exploration.is_in_dungeon());
+        assert_eq!(exploration.position(), (28, 12));
+        assert_eq!(exploration.light_radius, 0);
+    }
+
+    #[test]
+    fn test_rura_and_toheros() {
+        let mut exploration = exploration_at_level(30);
+        exploration.warp_to(100, 100);
+        let result = cast_field_spell(&mut exploration, Spell::Rura).unwrap();
+        assert_eq!(
+            result.effect,
+            FieldSpellEffect::Warped {
+                x: START_POSITION.0,
+                y: START_POSITION.1
+            }
+        );
+        assert_eq!(exploration.position(), START_POSITION);
+
+        cast_field_spell(&mut exploration, Spell::Toheros).unwrap();
+        assert_eq!(exploration.repel_steps, REPEL_STEPS);
+    }
+}
@@ -0,0 +1,325 @@
+use crate::battle::Battle;
+use crate::constants::monster::MONSTER_MASTER;
+use crate::death::{DeathOptions, Revival, apply_death_penalty};
+use crate::monster::Monster;
+use crate::player::Player;
+use crate::traits::message_output::MessageOutput;
+use crate::traits::player_input::PlayerInput;
+use crate::utility::monster_utils::get_monster_by_name;
+use serde::{Deserialize, Serialize};
+
+/// 宿代の初期値（ラダトームのやどや）
+pub const DEFAULT_INN_PRICE: u16 = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StageOutcome {
+    Defeated,
+    PlayerEscaped,
+    MonsterEscaped,
+    PlayerDied,
+}
+
+impl StageOutcome {
+    pub fn from_battle(battle: &Battle) -> Self {
+        if !battle.player.is_alive() {
+            StageOutcome::PlayerDied
+        } else if battle.player_state.escaped {
+            StageOutcome::PlayerEscaped
+        } else if battle.monster_state.escaped {
+            StageOutcome::MonsterEscaped
+        } else {
+            StageOutcome::Defeated
+        }
+    }
+}
+
+/// 1戦ごとの結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageReport {
+    pub monster_id: usize,
+    pub monster_name: String,
+    pub outcome: StageOutcome,
+    pub hp: u8, // 戦闘後（宿屋の前）
+    pub mp: u8,
+    pub herbs: u8,
+    pub exp_gained: u16,
+    pub gold_gained: u16,
+    pub rested: bool,             // 戦闘後に宿屋に泊まったか
+    pub revival: Option<Revival>, // しんだあと 王様の前で生き返ったか
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GauntletOptions {
+    pub rest_every: Option<usize>, // n 戦ごとに宿屋に泊まる
+    pub inn_price: u16,
+    pub revive: Option<DeathOptions>, // しんだら 死亡時のルールで生き返る
+}
+
+impl Default for GauntletOptions {
+    fn default() -> Self {
+        Self {
+            rest_every: None,
+            inn_price: DEFAULT_INN_PRICE,
+            revive: None,
+        }
+    }
+}
+
+pub struct GauntletResult {
+    pub player: Player,
+    pub stages: Vec<StageReport>,
+    pub total_stages: usize,
+}
+
+impl GauntletResult {
+    pub fn is_cleared(&self) -> bool {
+        self.stages.len() == self.total_stages
+            && self
+                .stages
+                .iter()
+                .all(|stage| stage.outcome != StageOutcome::PlayerDied)
+    }
+
+    pub fn total_exp(&self) -> u32 {
+        self.stages.iter().map(|s| s.exp_gained as u32).sum()
+    }
+
+    pub fn total_gold(&self) -> u32 {
+        self.stages.iter().map(|s| s.gold_gained as u32).sum()
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec!["--- けっか ---".to_string()];
+        for (i, stage) in self.stages.iter().enumerate() {
+            let outcome = match stage.outcome {
+                StageOutcome::Defeated => "たおした",
+                StageOutcome::PlayerEscaped => "にげだした",
+                StageOutcome::MonsterEscaped => "にげられた",
+                StageOutcome::PlayerDied => "しんだ",
+            };
+            lines.push(format!(
+                "{}: {} {} (HP: {} MP: {} やくそう: {}){}{}",
+                i + 1,
+                stage.monster_name,
+                outcome,
+                stage.hp,
+                stage.mp,
+                stage.herbs,
+                if stage.rested { " やどや" } else { "" },
+                stage
+                    .revival
+                    .as_ref()
+                    .map(|r| format!(" いきかえった (-{}G)", r.gold_lost))
+                    .unwrap_or_default()
+            ));
+        }
+        lines.push(format!(
+            "{} / {} 戦 {}",
+            self.stages.len(),
+            self.total_stages,
+            if self.is_cleared() {
+                "とっぱ"
+            } else {
+                "しっぱい"
+            }
+        ));
+        lines.push(format!(
+            "けいけんち +{} ゴールド +{}",
+            self.total_exp(),
+            self.total_gold()
+        ));
+        lines.push(format!("{:?}", self.player.summary()));
+        lines
+    }
+}
+
+/// 数字ならモンスターID、それ以外は名前として解決する
+pub fn resolve_monster_id(spec: &str) -> Result<usize, String> {
+    if let Ok(id) = spec.parse::<usize>() {
+        if id < MONSTER_MASTER.len() {
+            return Ok(id);
+        }
+        return Err(format!("Invalid monster id: {}", id));
+    }
+    get_monster_by_name(spec)
+        .map(|monster| monster.id as usize)
+        .ok_or_else(|| format!("Unknown monster: {}", spec))
+}
+
+pub fn resolve_monster_ids(specs: &[String]) -> Result<Vec<usize>, String> {
+    specs.iter().map(|spec| resolve_monster_id(spec)).collect()
+}
+
+/// 宿屋に泊まる。ゴールドが足りなければ泊まれない
+pub fn rest_at_inn(player: &mut Player, price: u16) -> bool {
+    if player.gold < price {
+        return false;
+    }
+    player.gold -= price;
+    player.hp_maximize();
+    player.mp_maximize();
+    true
+}
+
+/// 連戦する。HP・MP・どうぐ・呪いの状態・経験値・ゴールドは戦闘間で引き継ぐ
+/// 勇者が死んだら そこで終了（options.revive があれば ゴールドを半分失って生き返る）
+pub fn run_gauntlet(
+    player: Player,
+    monster_ids: &[usize],
+    options: &GauntletOptions,
+    input: &mut dyn PlayerInput,
+    output: &mut dyn MessageOutput,
+) -> GauntletResult {
+    let mut player = player;
+    let mut stages = Vec::new();
+
+    for (i, &monster_id) in monster_ids.iter().enumerate() {
+        let mut battle = Battle::new(player, Monster::new(monster_id), input, output);
+        battle.start();
+
+        let outcome = StageOutcome::from_battle(&battle);
+        let (exp_gained, gold_gained) = (battle.earned_exp, battle.earned_gold);
+        let monster_name = battle.monster.name().to_string();
+        player = battle.player;
+
+        let mut stage = StageReport {
+            monster_id,
+            monster_name,
+            outcome,
+            hp: player.hp,
+            mp: player.mp,
+            herbs: player.herbs,
+            exp_gained,
+            gold_gained,
+            rested: false,
+            revival: None,
+        };
+
+        let is_dead = stage.outcome == StageOutcome::PlayerDied;
+        let is_last = i + 1 == monster_ids.len();
+        if !is_dead
+            && !is_last
+            && options
+                .rest_every
+                .is_some_and(|n| n > 0 && (i + 1) % n == 0)
+        {
+            stage.rested = rest_at_inn(&mut player, options.inn_price);
+            if stage.rested {
+                output.output("やどやに とまった。HPとMPが かいふくした！");
+            } else {
+                output.output("ゴールドが たりないので やどやに とまれなかった");
+            }
+        }
+        if is_dead && let Some(death_options) = &options.revive {
+            let revival = apply_death_penalty(&mut player, death_options);
+            for line in revival.message_lines() {
+                output.output(&line);
+            }
+            stage.revival = Some(revival);
+        }
+        stages.push(stage);
+
+        if is_dead {
+            break;
+        }
+    }
+
+    GauntletResult {
+        player,
+        stages,
+        total_stages: monster_ids.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::auto_input::{AutoInput, AutoPolicy};
+    use crate::output::buffer_output::BufferOutput;
+    use crate::player::PlayerArgs;
+
+    #[test]
+    fn test_resolve_monster_ids_accepts_ids_and_names() {
+        let specs = vec!["0".to_string(), "メタルスライム".to_string()];
+        assert_eq!(resolve_monster_ids(&specs), Ok(vec![0, 27]));
+        assert!(resolve_monster_id("40").is_err());
+        assert!(resolve_monster_id("ファントム").is_err());
+    }
+
+    #[test]
+    fn test_rest_at_inn_requires_gold() {
+        let mut player = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(5),
+            gold: Some(10),
+            ..Default::default()
+        });
+        player.hp = 1;
+        assert!(rest_at_inn(&mut player, 6));
+        assert_eq!((player.hp, player.gold), (player.max_hp(), 4));
+        player.hp = 1;
+        assert!(!rest_at_inn(&mut player, 6));
+        assert_eq!(player.hp, 1);
+    }
+
+    #[test]
+    fn test_gauntlet_carries_state_between_battles() {
+        let mut player = Player::new("ゆうてい");
+        player.maximize();
+        player.exp = 0;
+        player.gold = 0;
+        player.hp = player.max_hp();
+        let mut input = AutoInput::new(AutoPolicy::attack_only());
+        let mut output = BufferOutput::new();
+        let result = run_gauntlet(
+            player,
+            &[0, 1, 2],
+            &GauntletOptions::default(),
+            &mut input,
+            &mut output,
+        );
+
+        assert_eq!(result.stages.len(), 3);
+        assert!(result.is_cleared());
+        assert_eq!(result.player.exp as u32, result.total_exp());
+        assert_eq!(result.player.gold as u32, result.total_gold());
+        assert!(result.player.to_password_string().is_ok());
+    }
+
+    #[test]
+    fn test_gauntlet_stops_when_player_dies() {
+        let mut player = Player::new("ゆうてい");
+        player.hp = 1;
+        let mut input = AutoInput::new(AutoPolicy::attack_only());
+        let mut output = BufferOutput::new();
+        let result = run_gauntlet(
+            player,
+            &[39, 0],
+            &GauntletOptions::default(),
+            &mut input,
+            &mut output,
+        );
+        assert_eq!(result.stages.len(), 1);
+        assert_eq!(result.stages[0].outcome, StageOutcome::PlayerDied);
+        assert!(!result.is_cleared());
+    }
+
+    #[test]
+    fn test_gauntlet_revives_with_death_penalty() {
+        let mut player = Player::new("ゆうてい");
+        player.hp = 1;
+        player.gold = 100;
+        let mut input = AutoInput::new(AutoPolicy::attack_only());
+        let mut output = BufferOutput::new();
+        let options = GauntletOptions {
+            revive: Some(DeathOptions::default()),
+            ..Default::default()
+        };
+        let result = run_gauntlet(player, &[39, 0], &options, &mut input, &mut output);
+        assert_eq!(result.stages.len(), 1);
+        assert!(!result.is_cleared());
+        assert!(result.stages[0].revival.is_some());
+        assert_eq!(result.player.gold, 50);
+        assert_eq!(result.player.hp, result.player.max_hp());
+    }
+}
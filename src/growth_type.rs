@@ -1,7 +1,8 @@
 use crate::constants::status::{DEFAULT_STATUS, STATUS_TABLE, Status};
 use crate::constants::text::KANA_TABLE;
 use crate::utility::status_utils::get_status_by_level;
-use crate::utility::string_utils::name_normalize;
+use crate::utility::string_utils::{join_dakuten, name_normalize};
+use serde::Serialize;
 use std::collections::HashMap;
 
 // 「゛」と「゜」のみ別
@@ -42,7 +43,12 @@ pub fn calculate_abc(total: u16) -> GrowthModifiers {
 }
 
 pub fn get_adjusted_status_list(name: &str) -> Vec<Status> {
-    let abc = calculate_abc(calculate_growth_name_total(&name));
+    get_status_list_by_growth_type(calculate_growth_name_total(&name))
+}
+
+/// せいちょうタイプ（calculate_growth_name_total の値 0〜15）ごとの レベル1〜30 の ステータス
+pub fn get_status_list_by_growth_type(growth_type: u16) -> Vec<Status> {
+    let abc = calculate_abc(growth_type % 16);
     STATUS_TABLE
         .iter()
         .map(|base| base.apply_abc_modifiers(&abc))
@@ -55,6 +61,126 @@ pub fn get_adjusted_status_by_name_lv(name: &str, lv: u8) -> Status {
     base.apply_abc_modifiers(&abc)
 }
 
+/// レベルごとの ステータス（と おぼえる じゅもん）
+pub fn status_curve_lines(statuses: &[Status]) -> Vec<String> {
+    statuses
+        .iter()
+        .map(|s| {
+            format!(
+                "  Lv{:>2} ちから{:>4} すばやさ{:>4} HP{:>4} MP{:>4} {}",
+                s.level,
+                s.strength,
+                s.agility,
+                s.max_hp,
+                s.max_mp,
+                s.spell.unwrap_or("")
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect()
+}
+
+/// ひとつの せいちょうタイプ の レベル1〜30 の ステータス
+#[derive(Debug, Clone, Serialize)]
+pub struct GrowthTable {
+    pub growth_type: u16,
+    pub name: Option<String>, // 名前から作ったときの 名前
+    pub statuses: Vec<Status>,
+}
+
+impl GrowthTable {
+    pub fn by_name(name: &str) -> Self {
+        Self {
+            growth_type: calculate_growth_name_total(name),
+            name: Some(join_dakuten(name_normalize(name).trim_end_matches('　'))),
+            statuses: get_adjusted_status_list(name),
+        }
+    }
+
+    pub fn by_growth_type(growth_type: u16) -> Self {
+        Self {
+            growth_type: growth_type % 16,
+            name: None,
+            statuses: get_status_list_by_growth_type(growth_type),
+        }
+    }
+
+    pub fn table_lines(&self) -> Vec<String> {
+        let abc = calculate_abc(self.growth_type);
+        let mut lines = vec![format!(
+            "せいちょうタイプ {}{}（a={} b={} c={}）",
+            self.growth_type,
+            self.name
+                .as_ref()
+                .map(|name| format!(" {}", name))
+                .unwrap_or_default(),
+            abc.a,
+            abc.b,
+            abc.c
+        )];
+        lines.extend(status_curve_lines(&self.statuses));
+        lines
+    }
+}
+
+/// せいちょうタイプごとの ステータス表（攻略用に table / CSV / JSON で出力する）
+#[derive(Debug, Clone, Serialize)]
+pub struct GrowthReport {
+    pub tables: Vec<GrowthTable>,
+}
+
+impl GrowthReport {
+    pub fn by_name(name: &str) -> Self {
+        Self {
+            tables: vec![GrowthTable::by_name(name)],
+        }
+    }
+
+    /// 16の せいちょうタイプ すべて
+    pub fn all() -> Self {
+        Self {
+            tables: (0..16).map(GrowthTable::by_growth_type).collect(),
+        }
+    }
+
+    pub fn table_lines(&self) -> Vec<String> {
+        self.tables
+            .iter()
+            .flat_map(|table| table.table_lines())
+            .collect()
+    }
+
+    pub fn csv_header() -> &'static str {
+        "growth_type,name,level,strength,agility,max_hp,max_mp,spell"
+    }
+
+    pub fn to_csv(&self) -> String {
+        std::iter::once(Self::csv_header().to_string())
+            .chain(self.tables.iter().flat_map(|table| {
+                table.statuses.iter().map(|s| {
+                    format!(
+                        "{},{},{},{},{},{},{},{}",
+                        table.growth_type,
+                        table.name.as_deref().unwrap_or(""),
+                        s.level,
+                        s.strength,
+                        s.agility,
+                        s.max_hp,
+                        s.max_mp,
+                        s.spell.unwrap_or("")
+                    )
+                })
+            }))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +243,29 @@ mod tests {
         // や=13, お=14, う=12, 空白=15 → 合計 = 13 + 14 + 12 + 15 = 54 → %16 = 6
         assert_eq!(calculate_growth_name_total("やおう"), 6);
     }
+
+    #[test]
+    fn test_growth_report() {
+        let report = GrowthReport::by_name("ゆうてい");
+        assert_eq!(report.tables[0].growth_type, 1);
+        assert_eq!(
+            GrowthReport::by_name("た゛い　").tables[0].name.as_deref(),
+            Some("だい")
+        );
+        assert_eq!(
+            report.tables[0].statuses[29].strength,
+            get_adjusted_status_by_name_lv("ゆうてい", 30).strength
+        );
+
+        let all = GrowthReport::all();
+        assert_eq!(all.tables.len(), 16);
+        assert_eq!(all.table_lines().len(), 16 * 31);
+        let csv = all.to_csv();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 1 + 16 * 30);
+        assert_eq!(rows[0], GrowthReport::csv_header());
+        assert!(rows[3].starts_with("0,,3,"));
+        assert!(rows[3].ends_with(",ホイミ"));
+        assert!(all.to_json().unwrap().contains("\"growth_type\": 15"));
+    }
 }
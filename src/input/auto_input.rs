@@ -0,0 +1,341 @@
+use crate::battle::BattleSnapshot;
+use crate::constants::battle::PlayerAction;
+use crate::constants::monster::{ActionType, MONSTER_BEHAVIORS};
+use crate::constants::spell::Spell;
+use crate::player::ItemKind;
+use crate::traits::player_input::PlayerInput;
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+/// ホイミの最大回復量。これ以上減っていればベホイミを優先する
+pub const HOIMI_MAX_HEAL: u8 = 17;
+/// この耐性（%）未満のモンスターにだけラリホー・マホトーンを使う
+pub const RESIST_LIMIT_PERCENT: u8 = 50;
+pub const DEFAULT_HEAL_THRESHOLD: u8 = 50;
+
+/// 自動戦闘の方針
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoPolicy {
+    pub heal_threshold: Option<u8>, // 最大HPに対する割合（%）。これを下回ったら回復
+    pub disable_casters: bool,      // 呪文・息を使う敵を先に眠らせる・封じる
+    pub flee_metal: bool,           // メタル系からは逃げる
+}
+
+impl AutoPolicy {
+    /// ひたすら こうげき
+    pub fn attack_only() -> Self {
+        Self {
+            heal_threshold: None,
+            disable_casters: false,
+            flee_metal: false,
+        }
+    }
+
+    /// HPが threshold% を下回ったら ベホイミ・ホイミ・やくそう で回復
+    pub fn healer(threshold: u8) -> Self {
+        Self {
+            heal_threshold: Some(threshold),
+            ..Self::attack_only()
+        }
+    }
+
+    /// 危険な敵を先に眠らせる・呪文を封じる
+    pub fn tactician() -> Self {
+        Self {
+            disable_casters: true,
+            ..Self::attack_only()
+        }
+    }
+
+    /// メタル系からは逃げる
+    pub fn metal_runner() -> Self {
+        Self {
+            flee_metal: true,
+            ..Self::attack_only()
+        }
+    }
+
+    /// 回復・呪文封じ・逃走をすべて行う
+    pub fn balanced() -> Self {
+        Self {
+            heal_threshold: Some(DEFAULT_HEAL_THRESHOLD),
+            disable_casters: true,
+            flee_metal: true,
+        }
+    }
+}
+
+impl Default for AutoPolicy {
+    fn default() -> Self {
+        Self::balanced()
+    }
+}
+
+impl FromStr for AutoPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "attack" => Ok(Self::attack_only()),
+            "heal" => Ok(Self::healer(DEFAULT_HEAL_THRESHOLD)),
+            "tactics" => Ok(Self::tactician()),
+            "flee" => Ok(Self::metal_runner()),
+            "balanced" => Ok(Self::balanced()),
+            _ => Err(format!("Invalid auto policy: {}", s)),
+        }
+    }
+}
+
+/// 方針に従ってコマンドとメニュー番号を選ぶ入力
+#[derive(Debug, Default)]
+pub struct AutoInput {
+    pub policy: AutoPolicy,
+    snapshot: Option<BattleSnapshot>,
+    pending_input: VecDeque<usize>,
+}
+
+impl AutoInput {
+    pub fn new(policy: AutoPolicy) -> Self {
+        Self {
+            policy,
+            snapshot: None,
+            pending_input: VecDeque::new(),
+        }
+    }
+
+    /// 行動と、続くメニューで選ぶ番号（0: もどる, 1..: 項目）を決める
+    pub fn decide(&self, snapshot: &BattleSnapshot) -> (PlayerAction, Option<usize>) {
+        if self.policy.flee_metal && is_metal(snapshot.monster_name) {
+            return (PlayerAction::Escape, None);
+        }
+        if let Some(threshold) = self.policy.heal_threshold
+            && is_below_threshold(snapshot, threshold)
+            && let Some(decision) = heal_decision(snapshot)
+        {
+            return decision;
+        }
+        if self.policy.disable_casters
+            && let Some(decision) = disable_decision(snapshot)
+        {
+            return decision;
+        }
+        (PlayerAction::Attack, None)
+    }
+}
+
+impl PlayerInput for AutoInput {
+    fn get_player_input(&mut self, max: usize) -> usize {
+        self.pending_input.pop_front().unwrap_or(0).min(max)
+    }
+
+    fn get_player_action(&mut self, _display_commands: &mut dyn FnMut()) -> PlayerAction {
+        self.pending_input.clear();
+        let Some(snapshot) = self.snapshot.take() else {
+            return PlayerAction::Attack;
+        };
+        let (action, menu_index) = self.decide(&snapshot);
+        if let Some(index) = menu_index {
+            self.pending_input.push_back(index);
+        }
+        action
+    }
+
+    fn observe_battle(&mut self, snapshot: &BattleSnapshot) {
+        self.snapshot = Some(snapshot.clone());
+    }
+}
+
+fn is_metal(monster_name: &str) -> bool {
+    monster_name.contains("メタル")
+}
+
+fn is_below_threshold(snapshot: &BattleSnapshot, threshold: u8) -> bool {
+    (snapshot.player_hp as u16) * 100 < (snapshot.player_max_hp as u16) * threshold as u16
+}
+
+/// 唱えられる呪文のメニュー番号
+fn castable_spell_index(snapshot: &BattleSnapshot, spell: Spell) -> Option<usize> {
+    if snapshot.player_sealed {
+        return None;
+    }
+    snapshot
+        .spells
+        .iter()
+        .position(|info| info.spell == spell && snapshot.player_mp >= info.mp_cost)
+        .map(|i| i + 1)
+}
+
+fn heal_decision(snapshot: &BattleSnapshot) -> Option<(PlayerAction, Option<usize>)> {
+    let missing = snapshot.player_max_hp.saturating_sub(snapshot.player_hp);
+    let hoimi = castable_spell_index(snapshot, Spell::Hoimi);
+    let behoimi = castable_spell_index(snapshot, Spell::Behoimi);
+    let spell = if missing > HOIMI_MAX_HEAL {
+        behoimi.or(hoimi)
+    } else {
+        hoimi.or(behoimi)
+    };
+    if let Some(index) = spell {
+        return Some((PlayerAction::Spell, Some(index)));
+    }
+    snapshot
+        .items
+        .iter()
+        .position(|item| item.kind == ItemKind::Herb)
+        .map(|i| (PlayerAction::Item, Some(i + 1)))
+}
+
+fn disable_decision(snapshot: &BattleSnapshot) -> Option<(PlayerAction, Option<usize>)> {
+    if snapshot.monster_asleep {
+        return None;
+    }
+    let behavior = &MONSTER_BEHAVIORS[snapshot.monster_id as usize];
+    let uses_spell = behavior
+        .actions
+        .iter()
+        .any(|a| matches!(a.action, ActionType::Spell(_)));
+    let uses_breath = behavior
+        .actions
+        .iter()
+        .any(|a| matches!(a.action, ActionType::Special(_)));
+
+    // 呪文しか使わない相手は封じれば十分。息を吐く相手は眠らせる
+    if uses_spell
+        && !snapshot.monster_sealed
+        && behavior.resist.mahoton < RESIST_LIMIT_PERCENT
+        && let Some(index) = castable_spell_index(snapshot, Spell::Mahoton)
+    {
+        return Some((PlayerAction::Spell, Some(index)));
+    }
+    let still_dangerous = uses_breath || (uses_spell && !snapshot.monster_sealed);
+    if still_dangerous
+        && behavior.resist.rariho < RESIST_LIMIT_PERCENT
+        && let Some(index) = castable_spell_index(snapshot, Spell::Rarirho)
+    {
+        return Some((PlayerAction::Spell, Some(index)));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::battle::Battle;
+    use crate::monster::Monster;
+    use crate::player::{Player, PlayerArgs};
+    use crate::traits::message_output::MessageOutput;
+
+    struct SilentOutput;
+
+    impl MessageOutput for SilentOutput {
+        fn output(&mut self, _message: &str) {}
+    }
+
+    fn snapshot_for(player: Player, monster_id: usize) -> BattleSnapshot {
+        let mut input = AutoInput::default();
+        let mut output = SilentOutput;
+        Battle::new(player, Monster::new(monster_id), &mut input, &mut output).snapshot()
+    }
+
+    fn player_with(level: u8, herbs: u8) -> Player {
+        Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(level),
+            herbs: Some(herbs),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_attack_only_always_attacks() {
+        let input = AutoInput::new(AutoPolicy::attack_only());
+        let mut snapshot = snapshot_for(player_with(10, 3), 27);
+        snapshot.player_hp = 1;
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Attack, None));
+    }
+
+    #[test]
+    fn test_healer_picks_spell_menu_index() {
+        let input = AutoInput::new(AutoPolicy::healer(50));
+        let mut snapshot = snapshot_for(player_with(17, 0), 0);
+        snapshot.player_mp = 255;
+
+        // ホイミで足りる減り方はホイミ
+        snapshot.player_max_hp = 30;
+        snapshot.player_hp = 14;
+        let (action, index) = input.decide(&snapshot);
+        assert_eq!(action, PlayerAction::Spell);
+        assert_eq!(snapshot.spells[index.unwrap() - 1].spell, Spell::Hoimi);
+
+        // 大きく減っていればベホイミ
+        snapshot.player_max_hp = 100;
+        snapshot.player_hp = 40;
+        let (_, index) = input.decide(&snapshot);
+        assert_eq!(snapshot.spells[index.unwrap() - 1].spell, Spell::Behoimi);
+
+        // しきい値以上なら こうげき
+        snapshot.player_hp = 50;
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Attack, None));
+    }
+
+    #[test]
+    fn test_healer_falls_back_to_herb() {
+        let input = AutoInput::new(AutoPolicy::healer(50));
+        let mut snapshot = snapshot_for(player_with(1, 2), 0);
+        snapshot.player_hp = 1;
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Item, Some(1)));
+
+        snapshot.items.clear();
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Attack, None));
+    }
+
+    #[test]
+    fn test_tactician_seals_caster() {
+        let input = AutoInput::new(AutoPolicy::tactician());
+
+        // まどうし（ラリホー・ギラ）はマホトーン
+        let mut snapshot = snapshot_for(player_with(10, 0), 12);
+        snapshot.player_mp = 255;
+        let (action, index) = input.decide(&snapshot);
+        assert_eq!(action, PlayerAction::Spell);
+        assert_eq!(snapshot.spells[index.unwrap() - 1].spell, Spell::Mahoton);
+
+        // 封じた後は こうげき
+        snapshot.monster_sealed = true;
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Attack, None));
+
+        // 呪文を封じられていたら唱えない
+        snapshot.monster_sealed = false;
+        snapshot.player_sealed = true;
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Attack, None));
+    }
+
+    #[test]
+    fn test_metal_runner_flees_metal_slime() {
+        let input = AutoInput::new(AutoPolicy::metal_runner());
+        let snapshot = snapshot_for(player_with(1, 0), 27);
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Escape, None));
+        let snapshot = snapshot_for(player_with(1, 0), 0);
+        assert_eq!(input.decide(&snapshot), (PlayerAction::Attack, None));
+    }
+
+    #[test]
+    fn test_auto_battle_finishes() {
+        let mut player = Player::new("ゆうてい");
+        player.maximize();
+        let mut input = AutoInput::new(AutoPolicy::balanced());
+        let mut output = SilentOutput;
+        let mut battle = Battle::new(player, Monster::new(38), &mut input, &mut output);
+        battle.start();
+        assert!(!battle.is_battle_continue());
+    }
+
+    #[test]
+    fn test_policy_from_str() {
+        assert_eq!(
+            AutoPolicy::from_str("attack"),
+            Ok(AutoPolicy::attack_only())
+        );
+        assert_eq!(AutoPolicy::from_str("Balanced"), Ok(AutoPolicy::balanced()));
+        assert!(AutoPolicy::from_str("unknown").is_err());
+    }
+}
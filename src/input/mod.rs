@@ -1,2 +1,3 @@
+pub mod auto_input;
 pub mod cli_input;
 pub mod web_input;
@@ -0,0 +1,236 @@
+use crate::constants::item_weapon::{
+    HERB_UNIFIED_ID, ITEM_MASTER, KEY_UNIFIED_ID, MAX_HERBS, MAX_KEYS, QUEST_ITEM_IDS,
+};
+use crate::player::{ItemKind, Player, UnifiedItem};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InventoryError {
+    UnknownItem(u8),
+    InventoryFull,
+    HerbLimit,
+    KeyLimit,
+    AlreadyOwned,
+    NotOwned,
+    QuestItem,
+    Cursed,
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InventoryError::UnknownItem(id) => write!(f, "そんな どうぐは ない（ID: {}）", id),
+            InventoryError::InventoryFull => write!(f, "もちものが いっぱいだ"),
+            InventoryError::HerbLimit => write!(f, "やくそうは これいじょう もてない"),
+            InventoryError::KeyLimit => write!(f, "かぎは これいじょう もてない"),
+            InventoryError::AlreadyOwned => write!(f, "それは すでに もっている"),
+            InventoryError::NotOwned => write!(f, "そのどうぐを もっていない"),
+            InventoryError::QuestItem => write!(f, "だいじなものを すてることは できない"),
+            InventoryError::Cursed => write!(f, "のろわれていて すてることが できない"),
+        }
+    }
+}
+
+impl std::error::Error for InventoryError {}
+
+/// id は unified_item_list の ID（やくそう 101、かぎ 102、それ以外は ITEM_MASTER）
+fn validate_id(id: u8) -> Result<(), InventoryError> {
+    match id {
+        HERB_UNIFIED_ID | KEY_UNIFIED_ID => Ok(()),
+        _ if id != 0 && (id as usize) < ITEM_MASTER.len() => Ok(()),
+        _ => Err(InventoryError::UnknownItem(id)),
+    }
+}
+
+fn is_owned(player: &Player, id: u8) -> bool {
+    match id {
+        HERB_UNIFIED_ID => player.herbs > 0,
+        KEY_UNIFIED_ID => player.keys > 0,
+        _ => id != 0 && player.items.contains(&id),
+    }
+}
+
+pub fn is_quest_item(id: u8) -> bool {
+    QUEST_ITEM_IDS.contains(&id)
+}
+
+/// どうぐを持てるか（やくそう・かぎ は 6個まで、それ以外は 8枠。非売品は ひとつしか持てない）
+pub fn can_add(player: &Player, id: u8) -> Result<(), InventoryError> {
+    validate_id(id)?;
+    match id {
+        HERB_UNIFIED_ID if player.herbs >= MAX_HERBS => Err(InventoryError::HerbLimit),
+        KEY_UNIFIED_ID if player.keys >= MAX_KEYS => Err(InventoryError::KeyLimit),
+        HERB_UNIFIED_ID | KEY_UNIFIED_ID => Ok(()),
+        _ if ITEM_MASTER[id as usize].price == 0 && player.items.contains(&id) => {
+            Err(InventoryError::AlreadyOwned)
+        }
+        _ if !player.items.contains(&0) => Err(InventoryError::InventoryFull),
+        _ => Ok(()),
+    }
+}
+
+/// どうぐを ひとつ加える。どうぐ は いちばん前の あいている枠に入る
+pub fn add_item(player: &mut Player, id: u8) -> Result<(), InventoryError> {
+    can_add(player, id)?;
+    match id {
+        HERB_UNIFIED_ID => player.herbs += 1,
+        KEY_UNIFIED_ID => player.keys += 1,
+        _ => {
+            if let Some(slot) = player.items.iter().position(|&item| item == 0) {
+                player.items[slot] = id;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// どうぐを ひとつ取り除く（使ったとき）。後ろの どうぐ は前に詰める
+pub fn remove_item(player: &mut Player, id: u8) -> Result<(), InventoryError> {
+    validate_id(id)?;
+    if !is_owned(player, id) {
+        return Err(InventoryError::NotOwned);
+    }
+    match id {
+        HERB_UNIFIED_ID => player.herbs -= 1,
+        KEY_UNIFIED_ID => player.keys -= 1,
+        _ => {
+            player.remove_item(id);
+        }
+    }
+    Ok(())
+}
+
+/// すてられるか。だいじなもの と のろわれて はずせない どうぐ は すてられない
+pub fn can_drop(player: &Player, id: u8) -> Result<(), InventoryError> {
+    validate_id(id)?;
+    if !is_owned(player, id) {
+        return Err(InventoryError::NotOwned);
+    }
+    if is_quest_item(id) {
+        return Err(InventoryError::QuestItem);
+    }
+    if player.is_stuck_item(id) {
+        return Err(InventoryError::Cursed);
+    }
+    Ok(())
+}
+
+/// どうぐを すてる
+pub fn drop_item(player: &mut Player, id: u8) -> Result<(), InventoryError> {
+    can_drop(player, id)?;
+    remove_item(player, id)
+}
+
+/// 原作の メニューと同じく ITEM_MASTER の順に並べ、あいている枠を後ろに寄せる
+pub fn sort_items(player: &mut Player) {
+    player.items.sort_by_key(|&id| (id == 0, id));
+}
+
+/// unified_item_list を どうぐ メニューの表示にする（やくそう・かぎ は個数つき）
+pub fn menu_lines(items: &[UnifiedItem]) -> Vec<String> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| match item.kind {
+            ItemKind::Herb | ItemKind::Key => {
+                format!("{}: {} （{}）", i + 1, item.name, item.count)
+            }
+            ItemKind::Equipment => format!("{}: {}", i + 1, item.name),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::item_weapon::{
+        CURSED_BELT_ID, ERDRICK_TOKEN_ID, FAIRY_WATER_ID, MAX_ITEM_SLOTS, TORCH_ID,
+    };
+
+    #[test]
+    fn test_add_item_respects_slots_and_caps() {
+        let mut player = Player::new("ゆうてい");
+        for _ in 0..MAX_HERBS {
+            add_item(&mut player, HERB_UNIFIED_ID).unwrap();
+        }
+        assert_eq!(
+            add_item(&mut player, HERB_UNIFIED_ID),
+            Err(InventoryError::HerbLimit)
+        );
+        assert_eq!(
+            add_item(&mut player, 0),
+            Err(InventoryError::UnknownItem(0))
+        );
+        assert_eq!(
+            add_item(&mut player, 15),
+            Err(InventoryError::UnknownItem(15))
+        );
+
+        add_item(&mut player, ERDRICK_TOKEN_ID).unwrap();
+        assert_eq!(
+            add_item(&mut player, ERDRICK_TOKEN_ID),
+            Err(InventoryError::AlreadyOwned)
+        );
+        for _ in 1..MAX_ITEM_SLOTS {
+            add_item(&mut player, TORCH_ID).unwrap();
+        }
+        assert_eq!(
+            add_item(&mut player, TORCH_ID),
+            Err(InventoryError::InventoryFull)
+        );
+        assert_eq!(player.items[0], ERDRICK_TOKEN_ID);
+    }
+
+    #[test]
+    fn test_drop_item_refuses_quest_and_cursed_items() {
+        let mut player = Player::new("ゆうてい");
+        player.items = [ERDRICK_TOKEN_ID, CURSED_BELT_ID, TORCH_ID, 0, 0, 0, 0, 0];
+        assert_eq!(
+            drop_item(&mut player, ERDRICK_TOKEN_ID),
+            Err(InventoryError::QuestItem)
+        );
+        player.equip_cursed_belt();
+        assert_eq!(
+            drop_item(&mut player, CURSED_BELT_ID),
+            Err(InventoryError::Cursed)
+        );
+        assert_eq!(
+            drop_item(&mut player, FAIRY_WATER_ID),
+            Err(InventoryError::NotOwned)
+        );
+        drop_item(&mut player, TORCH_ID).unwrap();
+        assert_eq!(
+            player.items,
+            [ERDRICK_TOKEN_ID, CURSED_BELT_ID, 0, 0, 0, 0, 0, 0]
+        );
+
+        player.lift_curse();
+        drop_item(&mut player, CURSED_BELT_ID).unwrap();
+        assert_eq!(
+            drop_item(&mut player, KEY_UNIFIED_ID),
+            Err(InventoryError::NotOwned)
+        );
+    }
+
+    #[test]
+    fn test_sort_and_menu_lines() {
+        let mut player = Player::new("ゆうてい");
+        player.items = [0, TORCH_ID, ERDRICK_TOKEN_ID, 0, FAIRY_WATER_ID, 0, 0, 0];
+        player.herbs = 2;
+        sort_items(&mut player);
+        assert_eq!(
+            player.items,
+            [TORCH_ID, FAIRY_WATER_ID, ERDRICK_TOKEN_ID, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            menu_lines(&player.unified_item_list()),
+            vec![
+                "1: やくそう （2）",
+                "2: たいまつ",
+                "3: せいすい",
+                "4: ロトのしるし"
+            ]
+        );
+    }
+}
@@ -1,33 +1,72 @@
 pub mod battle;
+pub mod calculator;
 pub mod constants;
+pub mod death;
+pub mod dialogue;
+pub mod dungeon;
+pub mod encounter;
+pub mod equipment;
+pub mod exploration;
+pub mod field_item;
+pub mod field_spell;
+pub mod gauntlet;
 pub mod growth_type;
 pub mod input;
+pub mod inventory;
 pub mod load;
 pub mod message;
 pub mod monster;
+pub mod name_optimizer;
 pub mod output;
 pub mod player;
+pub mod quest;
+pub mod route;
 pub mod save;
+pub mod shop;
+pub mod survival;
+pub mod town_service;
 pub mod traits;
 pub mod utility;
+pub mod world;
+pub mod world_state;
 
 // WASM module (only compiled for wasm32 target)
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 
 use battle::Battle;
+use calculator::analyze_matchup;
 pub use constants::config::Cli;
 use constants::config::{DISPLAY_CATEGORY_LIST, Mode};
 use constants::item_weapon::{ARMOR_MASTER, ITEM_MASTER, SHIELD_MASTER, WEAPON_MASTER};
+use constants::message::NPC_MASTER;
 use constants::monster::MONSTER_MASTER;
 use constants::spell::SPELL_INFO_LIST;
 use constants::status::STATUS_TABLE;
+use constants::town::TOWN_MASTER;
+use death::DeathOptions;
+use dialogue::{render_message, speak};
+use dungeon::{legend as dungeon_legend, load_dungeons};
+use equipment::{EquipSlot, best_affordable, equip, unequip};
+use exploration::{Exploration, ExploreCommand};
+use gauntlet::{GauntletOptions, resolve_monster_ids, run_gauntlet};
+use growth_type::GrowthReport;
+use input::auto_input::{AutoInput, AutoPolicy};
 use input::cli_input::CliInput;
 use monster::Monster;
+use name_optimizer::{NameGoal, NameSearch, optimize_name};
 use output::cli_output::CliOutput;
 use player::Player;
+use quest::QuestProgress;
+use route::{RouteOptions, find_route, parse_location};
+use shop::run_shop;
 use std::collections::HashSet;
-use utility::status_utils::{get_status_by_level, get_status_list};
+use survival::{all_monster_ids, analyze_survival_matrix};
+use town_service::{TownService, remove_curse, stay_at_inn, talk_to_king};
+use traits::player_input::PlayerInput;
+use utility::status_utils::get_status_by_level;
+use utility::town_utils::shop_entries;
+use world::{World, legend as world_legend};
 
 pub fn run_from_args(args: Cli) -> Result<(), Box<dyn std::error::Error>> {
     let views: HashSet<String> = args
@@ -71,11 +110,27 @@ pub fn run_from_args(args: Cli) -> Result<(), Box<dyn std::error::Error>> {
             println!("player name: {}", new_player.name);
             println!("summary: {:?}", new_player.summary());
             println!("strength_status: {:?}", new_player.strength_status());
+            for line in QuestProgress::new(&new_player, false).summary_lines() {
+                println!("{}", line);
+            }
         }
         Mode::Status => {
             if is_list_mode {
-                // todo: 強化params
-                println!("{:?}", get_status_list());
+                // --view list は -n の名前、--view list all は 16の せいちょうタイプ すべて
+                let report = if views.contains("all") {
+                    GrowthReport::all()
+                } else {
+                    GrowthReport::by_name(&player.name)
+                };
+                match args.format.as_deref() {
+                    Some("json") => println!("{}", report.to_json()?),
+                    Some("csv") => println!("{}", report.to_csv()),
+                    _ => {
+                        for line in report.table_lines() {
+                            println!("{}", line);
+                        }
+                    }
+                }
             } else {
                 println!("status: {:?}", get_status_by_level(player.level()));
             }
@@ -88,24 +143,325 @@ pub fn run_from_args(args: Cli) -> Result<(), Box<dyn std::error::Error>> {
             Some("status") => print_list_or_index(&STATUS_TABLE, is_list_mode, index),
             Some("spell") => print_list_or_index(&SPELL_INFO_LIST, is_list_mode, index),
             Some("monster") => print_list_or_index(&MONSTER_MASTER, is_list_mode, index),
+            Some("town") => {
+                print_list_or_index(&TOWN_MASTER, is_list_mode, index);
+                if let (false, Some(i)) = (is_list_mode, index) {
+                    let town = &TOWN_MASTER[i.min(TOWN_MASTER.len() - 1)];
+                    for entry in shop_entries(town) {
+                        println!("{}: {}G", entry.name, entry.price);
+                    }
+                }
+            }
+            Some("message") => match (is_list_mode, index) {
+                (false, Some(i)) => {
+                    let npc = &NPC_MASTER[i.min(NPC_MASTER.len() - 1)];
+                    println!("{} {} ({}, {})", npc.town, npc.name, npc.x, npc.y);
+                    for line in npc.lines.iter() {
+                        println!(
+                            "[{}] {}",
+                            line.condition.label(),
+                            render_message(line.text, &player.name)
+                        );
+                    }
+                    println!("> {}", speak(npc, &player));
+                }
+                _ => {
+                    for (i, npc) in NPC_MASTER.iter().enumerate() {
+                        println!("{}: {} {} ({}, {})", i, npc.town, npc.name, npc.x, npc.y);
+                    }
+                }
+            },
+            Some("world") => {
+                // --view world [x y [width height]]
+                let numbers: Vec<usize> = args
+                    .view
+                    .clone()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|s| s.parse::<usize>().ok())
+                    .collect();
+                let world = World::new();
+                let lines = match numbers.as_slice() {
+                    [x, y, width, height, ..] => world.render_region(*x, *y, *width, *height),
+                    [x, y] => world.render_region(*x, *y, 20, 20),
+                    _ => world.render(),
+                };
+                for line in lines {
+                    println!("{}", line);
+                }
+                println!("{}", world_legend());
+            }
+            Some("dungeon") => {
+                // --view dungeon [index floor]
+                let numbers: Vec<usize> = args
+                    .view
+                    .clone()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|s| s.parse::<usize>().ok())
+                    .collect();
+                let dungeons = load_dungeons()?;
+                match numbers.as_slice() {
+                    [i, floor, ..] => {
+                        let dungeon = &dungeons[(*i).min(dungeons.len() - 1)];
+                        let floor = dungeon
+                            .floor(*floor as u8)
+                            .ok_or_else(|| format!("{}に {}かいは ない", dungeon.name, floor))?;
+                        println!(
+                            "{} {}かい{}",
+                            dungeon.name,
+                            floor.number,
+                            if floor.dark { "（くらやみ）" } else { "" }
+                        );
+                        for line in floor.render() {
+                            println!("{}", line);
+                        }
+                        println!("{}", dungeon_legend());
+                    }
+                    _ => {
+                        for (i, dungeon) in dungeons.iter().enumerate() {
+                            println!("{}: {} ({}かいまで)", i, dungeon.name, dungeon.floors.len());
+                        }
+                    }
+                }
+            }
+            Some("quest") => {
+                let progress = QuestProgress::new(&player, false);
+                for line in progress.summary_lines() {
+                    println!("{}", line);
+                }
+                let missing = progress.missing_for_ending();
+                let names: Vec<&str> = missing.iter().map(|m| m.as_str()).collect();
+                println!("エンディングまでに たりないもの: {}", names.join("、"));
+            }
             None => println!("Category not specified"),
             _ => {}
         },
+        Mode::Damage => {
+            let monster = Monster::new(index.unwrap_or(0));
+            let report = analyze_matchup(&player, &monster);
+            println!("{} vs {}", report.player_name, report.monster_name);
+            println!("--- {} ---", report.player_name);
+            for damage in std::iter::once(&report.player_attack).chain(&report.player_spells) {
+                println!("{}", damage.pretty_string());
+                println!("  {}", damage.distribution_string());
+            }
+            println!("--- {} ---", report.monster_name);
+            for damage in std::iter::once(&report.monster_attack).chain(&report.monster_actions) {
+                println!("{}", damage.pretty_string());
+                println!("  {}", damage.distribution_string());
+            }
+        }
+        Mode::Survival => {
+            let monster_ids = index.map(|i| vec![i]).unwrap_or_else(all_monster_ids);
+            let matrix = analyze_survival_matrix(&player, &monster_ids);
+            match args.format.as_deref() {
+                Some("json") => println!("{}", matrix.to_json()?),
+                Some("csv") => println!("{}", matrix.to_csv()),
+                _ => {
+                    for recommended in &matrix.recommended_levels {
+                        match recommended.level {
+                            Some(level) => println!("{}: LV {}", recommended.monster_name, level),
+                            None => println!("{}: -", recommended.monster_name),
+                        }
+                    }
+                }
+            }
+        }
         Mode::Battle => {
             let mut output = CliOutput;
-            let mut input = CliInput;
+            let mut input = player_input(args.auto.as_deref())?;
             let mut battle = Battle::new(
                 player,
                 Monster::new(index.unwrap_or(0)),
-                &mut input,
+                input.as_mut(),
                 &mut output,
             );
             battle.start();
         }
+        Mode::Gauntlet => {
+            let monster_ids = resolve_monster_ids(&args.view.clone().unwrap_or_default())?;
+            let options = GauntletOptions {
+                rest_every: args.option.iter().any(|opt| opt == "inn").then_some(1),
+                revive: DeathOptions::from_words(&args.option),
+                ..Default::default()
+            };
+            let mut output = CliOutput;
+            let mut input = player_input(args.auto.as_deref())?;
+            let result = run_gauntlet(player, &monster_ids, &options, input.as_mut(), &mut output);
+            for line in result.summary_lines() {
+                println!("{}", line);
+            }
+            println!("password: {}", result.player.to_password_string()?);
+        }
+        Mode::Shop => {
+            let town = &TOWN_MASTER[index.unwrap_or(1).min(TOWN_MASTER.len() - 1)];
+            let mut output = CliOutput;
+            let mut input = CliInput;
+            run_shop(&mut player, town, &mut input, &mut output);
+            println!("summary: {:?}", player.summary());
+            println!("strength_status: {:?}", player.strength_status());
+            println!("item: {:?}", player.item_list());
+            println!("password: {}", player.to_password_string()?);
+        }
+        Mode::Explore => {
+            let commands = args
+                .view
+                .clone()
+                .unwrap_or_default()
+                .iter()
+                .map(|s| s.parse::<ExploreCommand>())
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut output = CliOutput;
+            let mut input = player_input(args.auto.as_deref())?;
+            let mut exploration = Exploration::new(player);
+            exploration.revive = DeathOptions::from_words(&args.option);
+            for command in commands {
+                for event in exploration.execute(command, input.as_mut(), &mut output) {
+                    println!("{}", event.message());
+                }
+            }
+            let (x, y) = exploration.position();
+            if let Some(location) = &exploration.dungeon {
+                println!("dungeon: {} {}かい", location.name, location.floor);
+            }
+            for line in exploration.render_view().unwrap_or_default() {
+                println!("{}", line);
+            }
+            println!("position: ({}, {}) {}", x, y, exploration.facing.as_str());
+            println!(
+                "steps: {} (HP -{} +{})",
+                exploration.steps, exploration.step_damage, exploration.step_healed
+            );
+            println!("summary: {:?}", exploration.player.summary());
+            println!("password: {}", exploration.player.to_password_string()?);
+        }
+        Mode::Route => {
+            // --view <from> <to> [avoid-damage] [avoid-danger]
+            let view = args.view.clone().unwrap_or_default();
+            let [from, to, options @ ..] = view.as_slice() else {
+                return Err("Route needs --view <from> <to>".into());
+            };
+            let world = World::new();
+            let start = parse_location(&world, from)?;
+            let goal = parse_location(&world, to)?;
+            let options = RouteOptions::from_words(options)?;
+            let route = find_route(&world, &player, start, goal, &options)?;
+            println!("route: {} {:?} -> {} {:?}", from, start, to, goal);
+            println!("moves: {}", route.moves_string());
+            println!("steps: {} cost: {}", route.moves.len(), route.cost);
+            println!("expected encounters: {:.2}", route.expected_encounters);
+            println!(
+                "HP: {} -> {} (-{} +{}){}",
+                route.walk.hp_start,
+                route.walk.hp_end,
+                route.walk.damage,
+                route.walk.healed,
+                if route.walk.died {
+                    " しんでしまう"
+                } else {
+                    ""
+                }
+            );
+            for line in route.render_overlay(&world, 2) {
+                println!("{}", line);
+            }
+            println!("@=しゅっぱつ !=もくてき *=みち {}", world_legend());
+        }
+        Mode::Service => {
+            // --view inn [town] | king | curse
+            let view = args.view.clone().unwrap_or_default();
+            let service = view
+                .first()
+                .ok_or("Service needs --view <inn|king|curse>")?
+                .parse::<TownService>()?;
+            let lines = match service {
+                TownService::Inn => {
+                    let town = &TOWN_MASTER[index.unwrap_or(1).min(TOWN_MASTER.len() - 1)];
+                    stay_at_inn(&mut player, town).map(|stay| stay.message_lines())
+                }
+                TownService::King => talk_to_king(&player).map(|save| save.message_lines()),
+                TownService::Curse => {
+                    remove_curse(&mut player).map(|removal| removal.message_lines())
+                }
+            };
+            match lines {
+                Ok(lines) => lines.iter().for_each(|line| println!("{}", line)),
+                Err(error) => println!("{}", error),
+            }
+            println!("summary: {:?}", player.summary());
+            println!("item: {:?}", player.item_list());
+        }
+        Mode::Equip => {
+            // --view <weapon|armor|shield> <id> | unequip <slot> | best [gold]
+            let view = args.view.clone().unwrap_or_default();
+            let action = view
+                .first()
+                .ok_or("Equip needs --view <weapon|armor|shield|unequip|best>")?;
+            let lines = match action.to_lowercase().as_str() {
+                "best" => {
+                    let budget =
+                        index.map_or(player.gold, |gold| gold.min(u16::MAX as usize) as u16);
+                    Ok(best_affordable(&player, budget).message_lines())
+                }
+                "unequip" => {
+                    let slot = view
+                        .get(1)
+                        .ok_or("Equip unequip needs --view unequip <weapon|armor|shield>")?
+                        .parse::<EquipSlot>()?;
+                    unequip(&mut player, slot).map(|comparison| comparison.message_lines())
+                }
+                slot => {
+                    let slot = slot.parse::<EquipSlot>()?;
+                    let id = index.ok_or("Equip needs an equipment id")?;
+                    let id = u8::try_from(id).map_err(|_| format!("不正なIDです: {}", id))?;
+                    equip(&mut player, slot, id).map(|comparison| comparison.message_lines())
+                }
+            };
+            match lines {
+                Ok(lines) => lines.iter().for_each(|line| println!("{}", line)),
+                Err(error) => println!("{}", error),
+            }
+            println!("summary: {:?}", player.summary());
+            println!("strength_status: {:?}", player.strength_status());
+        }
+        Mode::Name => {
+            // --view <strength|mp|balanced> [start=<かな>] [words=<a,b,...|file>] [near] [limit=<n>]
+            let view = args.view.clone().unwrap_or_default();
+            let [goal, options @ ..] = view.as_slice() else {
+                return Err("Name needs --view <strength|mp|balanced>".into());
+            };
+            let goal = goal.parse::<NameGoal>()?;
+            // words= に ファイルを渡したときは 1行1語で読む
+            let options = options
+                .iter()
+                .map(|option| match option.strip_prefix("words=") {
+                    Some(path) if std::path::Path::new(path).is_file() => {
+                        std::fs::read_to_string(path).map(|text| {
+                            format!("words={}", text.lines().collect::<Vec<_>>().join(","))
+                        })
+                    }
+                    _ => Ok(option.clone()),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let search = NameSearch::from_words(&options, &args.name)?;
+            let report = optimize_name(goal, &search)?;
+            for line in report.message_lines() {
+                println!("{}", line);
+            }
+        }
     }
     Ok(())
 }
 
+/// --auto があれば 自動戦闘、なければ コマンドラインから入力する
+fn player_input(auto: Option<&str>) -> Result<Box<dyn PlayerInput>, Box<dyn std::error::Error>> {
+    Ok(match auto {
+        Some(policy) => Box::new(AutoInput::new(policy.parse::<AutoPolicy>()?)),
+        None => Box::new(CliInput),
+    })
+}
+
 fn print_list_or_index<T: std::fmt::Debug>(list: &[T], is_list_mode: bool, index: Option<usize>) {
     if is_list_mode {
         println!("{:?}", list);
@@ -4,7 +4,7 @@ use crate::constants::monster::{
 };
 use crate::constants::spell::Spell;
 use crate::player::Player;
-use crate::utility::random_utils::{generate_in_range, get_random_bonus, random_value};
+use crate::utility::random_utils::{generate_in_range, random_value};
 
 #[derive(Debug, Clone)]
 pub struct Monster {
@@ -139,40 +139,61 @@ impl Monster {
         self.behavior.index == 38 || self.behavior.index == 39
     }
 
-    pub fn correction_damage(&self, player: &Player) -> u8 {
-        let monster_strength = self.stats.attack as i32;
-        let player_defense = player.defense_power() as i32;
-        let base_damage = (monster_strength - (player_defense / 2) + 2).max(0) / 4;
-        let random_bonus = get_random_bonus(monster_strength);
+    /// 主人公の守備力が高い場合の弱いダメージ式（0 ～ (敵の攻撃力 + 4) / 6）
+    pub fn correction_damage(&self) -> u8 {
+        self.correction_damage_with_rand(random_value(BIT_8_MAX))
+    }
 
-        let mut damage = base_damage + random_bonus;
-        if damage <= 0 {
-            damage = 0;
-        }
-        damage.min(255) as u8
+    pub fn correction_damage_with_rand(&self, rand_val: u8) -> u8 {
+        let monster_strength = self.stats.attack as u32;
+        let max_damage = (monster_strength + 4) / 6;
+        let damage = (rand_val as u32 * (max_damage + 1)) / 256;
+        damage.min(BIT_8_MAX as u32) as u8
     }
 
+    /// 通常のダメージ式（(敵の攻撃力 - 主人公の守備力 / 2) の 1/4 ～ 1/2）
     pub fn normal_damage(&self, player: &Player) -> u8 {
-        let monster_strength = self.stats.attack as i32;
-        let player_defense = player.defense_power() as i32;
-        let diff = monster_strength - (player_defense / 2);
-        let rand_val = random_value(BIT_8_MAX) as i32;
-        let damage = (rand_val * (diff + 1) / 256 + diff) / 4;
+        self.normal_damage_with_rand(player, random_value(BIT_8_MAX))
+    }
+
+    pub fn normal_damage_with_rand(&self, player: &Player, rand_val: u8) -> u8 {
+        let diff = self.attack_diff(player);
+        let damage = (rand_val as i32 * (diff + 1) / 256 + diff) / 4;
         damage.max(0).min(BIT_8_MAX as i32) as u8
     }
 
-    pub fn battle_attack(&self, player: &Player) -> u8 {
+    fn attack_diff(&self, player: &Player) -> i32 {
         let monster_strength = self.stats.attack as i32;
         let player_defense = player.defense_power() as i32;
-        let diff = monster_strength - (player_defense / 2);
+        monster_strength - (player_defense / 2)
+    }
 
-        if diff >= (monster_strength / 2 + 1) {
-            self.normal_damage(player)
+    /// 敵の攻撃力 - 主人公の守備力 / 2 が 敵の攻撃力 / 2 + 1 以上なら通常のダメージ式を使う
+    pub fn is_normal_damage_formula(&self, player: &Player) -> bool {
+        let monster_strength = self.stats.attack as i32;
+        self.attack_diff(player) >= (monster_strength / 2 + 1)
+    }
+
+    pub fn battle_attack(&self, player: &Player) -> u8 {
+        self.battle_attack_with_rand(player, random_value(BIT_8_MAX))
+    }
+
+    pub fn battle_attack_with_rand(&self, player: &Player, rand_val: u8) -> u8 {
+        if self.is_normal_damage_formula(player) {
+            self.normal_damage_with_rand(player, rand_val)
         } else {
-            self.correction_damage(player)
+            self.correction_damage_with_rand(rand_val)
         }
     }
 
+    /// 通常攻撃の (最小, 最大) ダメージ
+    pub fn battle_attack_range(&self, player: &Player) -> (u8, u8) {
+        (
+            self.battle_attack_with_rand(player, 0),
+            self.battle_attack_with_rand(player, BIT_8_MAX),
+        )
+    }
+
     pub fn get_gold(&self) -> u8 {
         let max_gold = self.stats.gold;
         let min_gold = (max_gold as f32 * 0.75).floor() as u8;
@@ -230,6 +251,74 @@ mod tests {
         assert!(found, "Ryuoh should have “Ho-oh (strong)”.");
     }
 
+    fn armored_player(name: &str, armor: u8, shield: u8) -> Player {
+        Player::new_with(crate::player::PlayerArgs {
+            name: Some(name.to_string()),
+            armor: Some(armor),
+            shield: Some(shield),
+            ..Default::default()
+        })
+    }
+
+    fn maximized_player(name: &str) -> Player {
+        let mut player = Player::new(name);
+        player.maximize();
+        player
+    }
+
+    #[test]
+    fn test_battle_attack_selects_normal_formula() {
+        // スライム(攻撃力5) vs ゆうてい Lv1 (守備力1) → 5 - 0 >= 5 / 2 + 1
+        let slime = Monster::new(0);
+        let player = Player::new("ゆうてい");
+        assert!(slime.is_normal_damage_formula(&player));
+
+        // スライム vs はがねのよろい + みかがみのたて (守備力45) → 5 - 22 < 3
+        let armored = armored_player("ゆうてい", 5, 3);
+        assert!(!slime.is_normal_damage_formula(&armored));
+    }
+
+    #[test]
+    fn test_correction_damage_never_exceeds_original_cap() {
+        // がいこつ(攻撃力28) → 0 ～ (28 + 4) / 6 = 5
+        let skeleton = Monster::new(7);
+        let armored = armored_player("ゆうてい", 5, 3);
+        for _ in 0..200 {
+            assert!(skeleton.battle_attack(&armored) <= 5);
+        }
+    }
+
+    #[test]
+    fn test_battle_attack_range_fidelity_table() {
+        // (モンスター, プレイヤー, 最小, 最大)
+        let cases: Vec<(usize, Player, u8, u8)> = vec![
+            // スライム vs ゆうてい Lv1 素手 (守備力1): 通常式
+            (0, Player::new("ゆうてい"), 1, 2),
+            // スライム vs はがねのよろい + みかがみのたて (守備力45): 弱い式
+            (0, armored_player("ゆうてい", 5, 3), 0, 1),
+            // がいこつ vs はがねのよろい + みかがみのたて (守備力45): 弱い式
+            (7, armored_player("ゆうてい", 5, 3), 0, 5),
+            // ゴーレム vs だい 最強装備 (守備力115): 通常式
+            (32, maximized_player("だい"), 15, 31),
+            // りゅうおう(第二形態) vs だい 最強装備 (守備力115): 通常式
+            (39, maximized_player("だい"), 20, 41),
+            // りゅうおう(第二形態) vs ゆうてい 最強装備 (守備力108): 通常式
+            (39, maximized_player("ゆうてい"), 21, 43),
+        ];
+
+        for (index, player, min, max) in cases {
+            let monster = Monster::new(index);
+            assert_eq!(
+                monster.battle_attack_range(&player),
+                (min, max),
+                "{} vs {} (守備力{})",
+                monster.name(),
+                player.name,
+                player.defense_power()
+            );
+        }
+    }
+
     // #[test]
     // fn test_player_max_damage() {
     //     let mut player = Player::new("だい");
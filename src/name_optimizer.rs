@@ -0,0 +1,420 @@
+use crate::constants::status::Status;
+use crate::constants::text::{KANA_TABLE, NAME_MAX_LENGTH};
+use crate::growth_type::{
+    calculate_growth_name_total, get_adjusted_status_list, get_status_list_by_growth_type,
+    status_curve_lines,
+};
+use crate::utility::string_utils::{join_dakuten, name_normalize, split_dakuten};
+use serde::Serialize;
+use std::str::FromStr;
+
+/// 「はやい時期」の MP を合計するレベル
+pub const EARLY_LEVEL: u8 = 10;
+/// 名前の候補を いくつ出すか
+pub const DEFAULT_NAME_LIMIT: usize = 10;
+/// 名前を つくるときに使う文字（あ〜ん。数字・小さい文字・記号は使わない）
+const NAME_KANA_RANGE: std::ops::RangeInclusive<usize> = 10..=55;
+const GROWTH_TYPE_COUNT: u16 = 16;
+const BLANK: char = '　';
+
+/// どの つよさを 優先するか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum NameGoal {
+    Strength, // レベル30の ちから
+    EarlyMp,  // はやい時期の MP
+    Balanced, // レベル30の ちから・すばやさ・HP・MP の合計
+}
+
+impl FromStr for NameGoal {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "strength" | "str" | "ちから" => Ok(NameGoal::Strength),
+            "mp" | "early-mp" => Ok(NameGoal::EarlyMp),
+            "balanced" | "balance" | "バランス" => Ok(NameGoal::Balanced),
+            _ => Err(format!("不明な もくひょう です: {}", input)),
+        }
+    }
+}
+
+impl NameGoal {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NameGoal::Strength => "レベル30の ちから",
+            NameGoal::EarlyMp => "レベル10までの MP",
+            NameGoal::Balanced => "レベル30の ステータス合計",
+        }
+    }
+
+    /// (もくひょう の値, 同じ値のときに比べる レベル30の ステータス合計)
+    fn score(&self, statuses: &[Status]) -> (u32, u32) {
+        let total =
+            |s: &Status| s.strength as u32 + s.agility as u32 + s.max_hp as u32 + s.max_mp as u32;
+        let last = statuses.last().map_or(0, total);
+        match self {
+            NameGoal::Strength => (statuses.last().map_or(0, |s| s.strength as u32), last),
+            NameGoal::EarlyMp => (
+                statuses
+                    .iter()
+                    .filter(|s| s.level <= EARLY_LEVEL)
+                    .map(|s| s.max_mp as u32)
+                    .sum(),
+                last,
+            ),
+            NameGoal::Balanced => (last, statuses.iter().map(total).sum()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct GrowthScore {
+    pub growth_type: u16,
+    pub score: u32,
+}
+
+/// 16の せいちょうタイプを もくひょう の よい順に並べる（同じなら ステータス合計、タイプ番号の順）
+pub fn rank_growth_types(goal: NameGoal) -> Vec<GrowthScore> {
+    let mut scored: Vec<(u16, (u32, u32))> = (0..GROWTH_TYPE_COUNT)
+        .map(|growth_type| {
+            (
+                growth_type,
+                goal.score(&get_status_list_by_growth_type(growth_type)),
+            )
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored
+        .into_iter()
+        .map(|(growth_type, (score, _))| GrowthScore { growth_type, score })
+        .collect()
+}
+
+/// 名前の しぼりこみ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameSearch {
+    pub prefix: Option<String>, // この文字で はじまる
+    pub words: Vec<String>,     // この中から選ぶ（空なら 50音から つくる）
+    pub near: Option<String>,   // この名前に ちかい順
+    pub limit: usize,
+}
+
+impl Default for NameSearch {
+    fn default() -> Self {
+        Self {
+            prefix: None,
+            words: Vec::new(),
+            near: None,
+            limit: DEFAULT_NAME_LIMIT,
+        }
+    }
+}
+
+impl NameSearch {
+    /// start=<かな> / words=<ことば,ことば> / near / limit=<数> を受け付ける。near は my_name に ちかい順
+    pub fn from_words(words: &[String], my_name: &str) -> Result<Self, String> {
+        let mut search = Self::default();
+        for word in words {
+            match word.split_once('=') {
+                Some(("start", prefix)) => search.prefix = Some(prefix.to_string()),
+                Some(("words", list)) => search.words.extend(
+                    list.split(',')
+                        .map(str::trim)
+                        .filter(|w| !w.is_empty())
+                        .map(str::to_string),
+                ),
+                Some(("limit", limit)) => {
+                    search.limit = limit
+                        .parse()
+                        .map_err(|_| format!("Invalid name limit: {}", limit))?
+                }
+                None if word == "near" => search.near = Some(my_name.to_string()),
+                _ => return Err(format!("Invalid name option: {}", word)),
+            }
+        }
+        Ok(search)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NameSuggestion {
+    pub name: String,
+    pub growth_type: u16,
+    pub distance: Option<usize>, // near との ちがう文字の数
+    pub statuses: Vec<Status>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NameReport {
+    pub goal: NameGoal,
+    pub growth_type: u16,
+    pub score: u32,
+    pub ranking: Vec<GrowthScore>,
+    pub suggestions: Vec<NameSuggestion>,
+}
+
+impl NameReport {
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "もくひょう: {}  いちばん よい せいちょうタイプ: {}（{}）",
+            self.goal.label(),
+            self.growth_type,
+            self.score
+        )];
+        if self.suggestions.is_empty() {
+            lines.push("あてはまる なまえが ない".to_string());
+        }
+        for suggestion in &self.suggestions {
+            lines.push(match suggestion.distance {
+                Some(distance) => format!("なまえ: {}（ちがう文字 {}）", suggestion.name, distance),
+                None => format!("なまえ: {}", suggestion.name),
+            });
+            lines.extend(status_curve_lines(&suggestion.statuses));
+        }
+        lines
+    }
+}
+
+/// 濁音を分けた 4文字の枠（あまりは空白）
+fn name_slots(name: &str) -> Vec<char> {
+    name_normalize(name).chars().collect()
+}
+
+/// 名前に使えるか（濁音を分けて 4文字以内、すべて KANA_TABLE の文字）
+pub fn is_valid_name(name: &str) -> bool {
+    let split: Vec<char> = split_dakuten(name.trim()).chars().collect();
+    !split.is_empty()
+        && split.len() <= NAME_MAX_LENGTH
+        && split.iter().all(|c| KANA_TABLE.contains(c))
+}
+
+/// 4文字の枠で いくつ ちがうか
+pub fn name_distance(a: &str, b: &str) -> usize {
+    name_slots(a)
+        .iter()
+        .zip(name_slots(b))
+        .filter(|(x, y)| **x != *y)
+        .count()
+}
+
+fn slots_to_name(slots: &[char]) -> String {
+    join_dakuten(slots.iter().collect::<String>().trim_end_matches(BLANK))
+}
+
+/// もくひょう に いちばん あう せいちょうタイプと、その タイプになる名前を探す
+pub fn optimize_name(goal: NameGoal, search: &NameSearch) -> Result<NameReport, String> {
+    let prefix: Vec<char> = match &search.prefix {
+        Some(prefix) if !is_valid_name(prefix) => {
+            return Err(format!("なまえの はじめに つかえない文字です: {}", prefix));
+        }
+        Some(prefix) => split_dakuten(prefix).chars().collect(),
+        None => Vec::new(),
+    };
+    if let Some(near) = &search.near
+        && !is_valid_name(near)
+    {
+        return Err(format!("なまえに つかえない文字です: {}", near));
+    }
+
+    let ranking = rank_growth_types(goal);
+    let best = ranking[0];
+    let matches = |slots: &[char]| {
+        slots.starts_with(&prefix)
+            && calculate_growth_name_total(&slots.iter().collect::<String>()) == best.growth_type
+    };
+
+    let names: Vec<String> = if !search.words.is_empty() {
+        let mut names: Vec<String> = Vec::new();
+        for word in &search.words {
+            let word = word.trim();
+            if is_valid_name(word) && matches(&name_slots(word)) && !names.iter().any(|n| n == word)
+            {
+                names.push(word.to_string());
+            }
+        }
+        if let Some(near) = &search.near {
+            names.sort_by_key(|name| name_distance(name, near));
+        }
+        names.truncate(search.limit);
+        names
+    } else if let Some(near) = &search.near {
+        near_names(&name_slots(near), search.limit, &matches)
+    } else {
+        prefix_names(&prefix, search.limit, &matches)
+    };
+
+    let suggestions = names
+        .into_iter()
+        .map(|name| NameSuggestion {
+            growth_type: calculate_growth_name_total(&name),
+            distance: search.near.as_ref().map(|near| name_distance(&name, near)),
+            statuses: get_adjusted_status_list(&name),
+            name,
+        })
+        .collect();
+    Ok(NameReport {
+        goal,
+        growth_type: best.growth_type,
+        score: best.score,
+        ranking,
+        suggestions,
+    })
+}
+
+fn name_kana() -> &'static [char] {
+    &KANA_TABLE[NAME_KANA_RANGE]
+}
+
+/// prefix のあとを 50音順に うめた 4文字の名前
+fn prefix_names(prefix: &[char], limit: usize, matches: &dyn Fn(&[char]) -> bool) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut slots = prefix.to_vec();
+    fill_slots(&mut slots, limit, matches, &mut names);
+    names
+}
+
+fn fill_slots(
+    slots: &mut Vec<char>,
+    limit: usize,
+    matches: &dyn Fn(&[char]) -> bool,
+    names: &mut Vec<String>,
+) {
+    if names.len() >= limit {
+        return;
+    }
+    if slots.len() == NAME_MAX_LENGTH {
+        if matches(slots) {
+            names.push(slots_to_name(slots));
+        }
+        return;
+    }
+    for &c in name_kana() {
+        slots.push(c);
+        fill_slots(slots, limit, matches, names);
+        slots.pop();
+    }
+}
+
+/// near の文字を ちがう数が すくない順に 入れかえた名前
+fn near_names(near: &[char], limit: usize, matches: &dyn Fn(&[char]) -> bool) -> Vec<String> {
+    let mut names = Vec::new();
+    for distance in 0..=NAME_MAX_LENGTH {
+        let mut slots = near.to_vec();
+        replace_slots(&mut slots, near, 0, distance, limit, matches, &mut names);
+        if names.len() >= limit {
+            break;
+        }
+    }
+    names
+}
+
+fn replace_slots(
+    slots: &mut [char],
+    near: &[char],
+    from: usize,
+    remaining: usize,
+    limit: usize,
+    matches: &dyn Fn(&[char]) -> bool,
+    names: &mut Vec<String>,
+) {
+    if names.len() >= limit {
+        return;
+    }
+    if remaining == 0 {
+        if matches(slots) {
+            names.push(slots_to_name(slots));
+        }
+        return;
+    }
+    for position in from..slots.len() {
+        for &c in name_kana().iter().filter(|&&c| c != near[position]) {
+            slots[position] = c;
+            replace_slots(
+                slots,
+                near,
+                position + 1,
+                remaining - 1,
+                limit,
+                matches,
+                names,
+            );
+        }
+        slots[position] = near[position];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_growth_types() {
+        let ranking = rank_growth_types(NameGoal::Strength);
+        assert_eq!(ranking.len(), 16);
+        let best = get_status_list_by_growth_type(ranking[0].growth_type);
+        for growth_type in 0..16 {
+            let statuses = get_status_list_by_growth_type(growth_type);
+            assert!(statuses[29].strength <= best[29].strength);
+        }
+        assert!(ranking.windows(2).all(|w| w[0].score >= w[1].score));
+
+        let mp = rank_growth_types(NameGoal::EarlyMp)[0];
+        assert_eq!(
+            NameGoal::EarlyMp
+                .score(&get_status_list_by_growth_type(mp.growth_type))
+                .0,
+            mp.score
+        );
+    }
+
+    #[test]
+    fn test_optimize_name_with_prefix_and_words() {
+        let search = NameSearch {
+            prefix: Some("ゆう".to_string()),
+            limit: 3,
+            ..Default::default()
+        };
+        let report = optimize_name(NameGoal::Balanced, &search).unwrap();
+        assert_eq!(report.suggestions.len(), 3);
+        for suggestion in &report.suggestions {
+            assert!(suggestion.name.starts_with("ゆう"));
+            assert_eq!(suggestion.growth_type, report.growth_type);
+            assert_eq!(suggestion.statuses.len(), 30);
+        }
+
+        let best = report.growth_type;
+        let words = ["ゆうてい", "だい", "ABC", "あいうえおか"].map(str::to_string);
+        let search = NameSearch {
+            words: words.to_vec(),
+            ..Default::default()
+        };
+        let report = optimize_name(NameGoal::Balanced, &search).unwrap();
+        let expected: Vec<&str> = ["ゆうてい", "だい"]
+            .into_iter()
+            .filter(|w| calculate_growth_name_total(w) == best)
+            .collect();
+        let names: Vec<&str> = report.suggestions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, expected);
+
+        let bad = NameSearch {
+            prefix: Some("X".to_string()),
+            ..Default::default()
+        };
+        assert!(optimize_name(NameGoal::Balanced, &bad).is_err());
+    }
+
+    #[test]
+    fn test_optimize_name_near() {
+        let words = ["near".to_string(), "limit=5".to_string()];
+        let search = NameSearch::from_words(&words, "ゆうてい").unwrap();
+        let report = optimize_name(NameGoal::Strength, &search).unwrap();
+        assert_eq!(report.suggestions.len(), 5);
+        // 1文字 かえれば どの タイプにもなる
+        for suggestion in &report.suggestions {
+            assert!(suggestion.distance.unwrap() <= 1);
+            assert_eq!(suggestion.growth_type, report.growth_type);
+        }
+        assert_eq!(name_distance("だい", "ばい"), 1);
+        assert!(NameSearch::from_words(&["far".to_string()], "ゆうてい").is_err());
+    }
+}
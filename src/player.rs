@@ -1,10 +1,13 @@
+use crate::constants::field::{ARMOR_REGEN_HP, MAGIC_ARMOR_REGEN_STEPS};
 use crate::constants::item_weapon::{
-    ARMOR_MASTER, Equipment, ITEM_MASTER, SHIELD_MASTER, WEAPON_MASTER,
+    ARMOR_MASTER, CursedItem, DEATH_NECKLACE_HP_DIVISOR, DEATH_NECKLACE_ID, Equipment,
+    HERB_UNIFIED_ID, ITEM_MASTER, KEY_UNIFIED_ID, SHIELD_MASTER, WEAPON_MASTER,
 };
 use crate::constants::save_data::{SaveData, SaveDataArgs};
 use crate::constants::spell::SpellInfo;
 use crate::constants::status::{DEFAULT_STATUS, Flags, PlayerSummary, Status, StrengthStatus};
 use crate::constants::text::DEFAULT_NAME;
+use crate::constants::world::{BARRIER_DAMAGE, SWAMP_DAMAGE, Terrain};
 use crate::growth_type::{
     GrowthModifiers, calculate_abc, calculate_growth_name_total, get_adjusted_status_by_name_lv,
 };
@@ -14,8 +17,9 @@ use crate::utility::random_utils::{generate_in_range, random_value};
 use crate::utility::spell_utils::spells_learned_by_level;
 use crate::utility::status_utils::{get_level_by_exp, get_status_by_level, resolve_experience};
 use crate::utility::string_utils::name_normalize;
+use serde::Serialize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Player {
     pub name: String,
     pub hp: u8,
@@ -73,7 +77,7 @@ impl PlayerArgs {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct UnifiedItem {
     pub id: u8,
     pub name: &'static str,
@@ -81,7 +85,14 @@ pub struct UnifiedItem {
     pub kind: ItemKind,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// 1歩あるいたときの HP の変化
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StepEffect {
+    pub damage: u8,
+    pub healed: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum ItemKind {
     Herb,
     Key,
@@ -103,7 +114,7 @@ impl Player {
         let level = get_level_by_exp(final_exp);
         let adjusted = get_adjusted_status_by_name_lv(&name, level);
 
-        Self {
+        let mut player = Self {
             name,
             hp: adjusted.max_hp,
             mp: adjusted.max_mp,
@@ -118,7 +129,13 @@ impl Player {
             is_curse_belt: false,
             is_curse_necklace: false,
             flags: args.flags.unwrap_or_default(),
+        };
+        // 原作と同じく、のろわれた どうぐ を持っていれば ふっかつのじゅもん から戻ったときに のろわれる
+        let items = player.items;
+        for item in items.into_iter().filter_map(CursedItem::from_id) {
+            player.curse(item);
         }
+        player
     }
 
     pub fn from_password_string(s: &str) -> Result<Self, String> {
@@ -146,8 +163,14 @@ impl Player {
         self.status().map(|s| s.agility).unwrap_or(0)
     }
 
+    /// しのくびかざり で のろわれていると 1/4 へる
     pub fn max_hp(&self) -> u8 {
-        self.status().map(|s| s.max_hp).unwrap_or(0)
+        let max_hp = self.status().map(|s| s.max_hp).unwrap_or(0);
+        if self.is_curse_necklace {
+            max_hp - max_hp / DEATH_NECKLACE_HP_DIVISOR
+        } else {
+            max_hp
+        }
     }
 
     pub fn max_mp(&self) -> u8 {
@@ -230,7 +253,7 @@ impl Player {
 
         if self.herbs > 0 {
             result.push(UnifiedItem {
-                id: 101,
+                id: HERB_UNIFIED_ID,
                 name: "やくそう",
                 count: self.herbs,
                 kind: ItemKind::Herb,
@@ -238,7 +261,7 @@ impl Player {
         }
         if self.keys > 0 {
             result.push(UnifiedItem {
-                id: 102,
+                id: KEY_UNIFIED_ID,
                 name: "かぎ",
                 count: self.keys,
                 kind: ItemKind::Key,
@@ -290,13 +313,21 @@ impl Player {
             items: Some(self.items),
             herbs: Some(self.herbs),
             keys: Some(self.keys),
-            flags: Some(self.flags.clone()),
+            flags: Some(self.password_flags()),
             pattern: None,
             ..Default::default()
         });
         save.to_password_string()
     }
 
+    /// ふっかつのじゅもん に入れるフラグ。しのくびかざり を持っていれば 入手済みにする
+    fn password_flags(&self) -> Flags {
+        let mut flags = self.flags.clone();
+        flags.has_cursed_necklace |=
+            self.is_curse_necklace || self.items.contains(&DEATH_NECKLACE_ID);
+        flags
+    }
+
     pub fn hp_maximize(&mut self) {
         self.hp = self.max_hp()
     }
@@ -347,7 +378,7 @@ impl Player {
             level: self.level(),
             strength: status.strength,
             agility: status.agility,
-            max_hp: status.max_hp,
+            max_hp: self.max_hp(),
             max_mp: status.max_mp,
             attack_power: self.attack_power(),
             defense_power: self.defense_power(),
@@ -358,19 +389,27 @@ impl Player {
     }
 
     pub fn normal_damage(&self, monster: &Monster) -> u8 {
+        self.normal_damage_with_rand(monster, random_value(255))
+    }
+
+    pub fn normal_damage_with_rand(&self, monster: &Monster, rand_val: u8) -> u8 {
         let attack = self.attack_power();
         let defense = monster.stats.defense;
         let effective_defense = defense / 2;
         let base = attack.saturating_sub(effective_defense);
         let base_plus = base.saturating_add(1);
-        let rand_val = random_value(255) as u32;
+        let rand_val = rand_val as u32;
         let damage = ((rand_val * base_plus as u32) / 256 + base as u32) / 4;
         damage.min(255) as u8
     }
 
     pub fn critical_damage(&self) -> u8 {
+        self.critical_damage_with_rand(random_value(255))
+    }
+
+    pub fn critical_damage_with_rand(&self, rand_val: u8) -> u8 {
         let attack = self.attack_power();
-        let rand_val = random_value(255) as u32;
+        let rand_val = rand_val as u32;
         let damage = attack as u32 - (attack as u32 / 2 * rand_val) / 256;
         damage.min(255) as u8
     }
@@ -387,6 +426,51 @@ impl Player {
         self.armor == 6
     }
 
+    /// どくのぬまち・バリア で1歩ごとに受けるダメージ（ロトのよろい なら受けない）
+    pub fn terrain_damage(&self, terrain: Terrain) -> u8 {
+        if self.is_max_armor() {
+            return 0;
+        }
+        match terrain {
+            Terrain::Swamp => SWAMP_DAMAGE,
+            Terrain::Barrier => BARRIER_DAMAGE,
+            _ => 0,
+        }
+    }
+
+    /// step 歩目に よろい で回復する HP
+    pub fn armor_regen(&self, step: u32) -> u8 {
+        if self.is_max_armor()
+            || (self.is_magic_armor() && step.is_multiple_of(MAGIC_ARMOR_REGEN_STEPS))
+        {
+            ARMOR_REGEN_HP
+        } else {
+            0
+        }
+    }
+
+    /// HP が hp のとき step 歩目に terrain（ダンジョンの中なら None）へ進んだあとの HP と その変化
+    pub fn step_hp(&self, hp: u8, terrain: Option<Terrain>, step: u32) -> (u8, StepEffect) {
+        let damage = terrain.map_or(0, |t| self.terrain_damage(t));
+        let hp_after_damage = hp.saturating_sub(damage);
+        if hp_after_damage == 0 {
+            return (0, StepEffect { damage, healed: 0 });
+        }
+        let hp_after = hp_after_damage
+            .saturating_add(self.armor_regen(step))
+            .min(self.max_hp())
+            .max(hp_after_damage);
+        let healed = hp_after - hp_after_damage;
+        (hp_after, StepEffect { damage, healed })
+    }
+
+    /// step_hp の結果を反映する
+    pub fn take_step(&mut self, terrain: Option<Terrain>, step: u32) -> StepEffect {
+        let (hp, effect) = self.step_hp(self.hp, terrain, step);
+        self.hp = hp;
+        effect
+    }
+
     /// Spell Damage Correction
     pub fn reduce_spell_damage(&self, base_damage: u8) -> u8 {
         if self.is_magic_armor() || self.is_max_armor() {
@@ -411,24 +495,71 @@ impl Player {
         self.herbs -= 1;
     }
 
+    /// どうぐを1つ取り除き、後ろのどうぐを前に詰める
+    pub fn remove_item(&mut self, id: u8) -> bool {
+        let Some(slot) = self.items.iter().position(|&item| item != 0 && item == id) else {
+            return false;
+        };
+        self.items.copy_within(slot + 1.., slot);
+        self.items[self.items.len() - 1] = 0;
+        true
+    }
+
     pub fn equip_warrior_ring(&mut self) {
         self.flags.has_warrior_ring = true;
     }
 
     pub fn equip_cursed_belt(&mut self) {
-        self.flags.has_cursed_necklace = true;
-        self.is_curse_belt = true;
+        self.curse(CursedItem::Belt);
     }
 
     pub fn equip_cursed_necklace(&mut self) {
-        self.flags.has_cursed_necklace = true;
-        self.is_curse_necklace = true;
+        self.curse(CursedItem::Necklace);
+    }
+
+    /// のろわれた どうぐ を みにつける。しのくびかざり は 最大HP が へるので HP もおさえる
+    pub fn curse(&mut self, item: CursedItem) {
+        match item {
+            CursedItem::Belt => self.is_curse_belt = true,
+            CursedItem::Necklace => {
+                self.is_curse_necklace = true;
+                self.flags.has_cursed_necklace = true;
+                self.hp = self.hp.min(self.max_hp());
+            }
+        }
+    }
+
+    pub fn is_cursed(&self) -> bool {
+        self.is_curse_belt || self.is_curse_necklace
+    }
+
+    /// いま のろわれている どうぐ
+    pub fn cursed_items(&self) -> Vec<CursedItem> {
+        [
+            (self.is_curse_belt, CursedItem::Belt),
+            (self.is_curse_necklace, CursedItem::Necklace),
+        ]
+        .into_iter()
+        .filter_map(|(is_cursed, item)| is_cursed.then_some(item))
+        .collect()
+    }
+
+    /// のろわれていて はずせない どうぐ か（すてる・うる ことができない）
+    pub fn is_stuck_item(&self, id: u8) -> bool {
+        CursedItem::from_id(id).is_some_and(|item| self.cursed_items().contains(&item))
+    }
+
+    /// のろいを解く（どうぐ はそのまま残る）
+    pub fn lift_curse(&mut self) {
+        self.is_curse_belt = false;
+        self.is_curse_necklace = false;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::item_weapon::CURSED_BELT_ID;
     use crate::constants::status::DEFAULT_STATUS;
 
     #[test]
@@ -524,4 +655,96 @@ mod tests {
         assert_eq!(player_2_max_status.max_hp, 210);
         assert_eq!(player_2_max_status.max_mp, 180);
     }
+
+    #[test]
+    fn test_remove_item_shifts_items() {
+        let mut player = Player::new("ゆうてい");
+        player.items = [1, 2, 1, 3, 0, 0, 0, 0];
+        assert!(player.remove_item(1));
+        assert_eq!(player.items, [2, 1, 3, 0, 0, 0, 0, 0]);
+        assert!(!player.remove_item(4));
+        assert!(!player.remove_item(0));
+    }
+
+    #[test]
+    fn test_take_step_with_armor() {
+        let mut player = Player::new("ゆうてい");
+        player.hp = 10;
+        assert_eq!(
+            player.take_step(Some(Terrain::Swamp), 1),
+            StepEffect {
+                damage: SWAMP_DAMAGE,
+                healed: 0
+            }
+        );
+        assert_eq!(player.hp, 8);
+
+        // まほうのよろい は 4歩ごとに回復する
+        player.armor = 6;
+        assert_eq!(player.take_step(Some(Terrain::Grass), 3).healed, 0);
+        assert_eq!(player.take_step(Some(Terrain::Grass), 4).healed, 1);
+        assert_eq!(
+            player.take_step(Some(Terrain::Barrier), 8).damage,
+            BARRIER_DAMAGE
+        );
+
+        // ロトのよろい は ダメージを受けず 毎歩回復する（最大HPまで）
+        player.armor = 7;
+        player.hp = 5;
+        assert_eq!(
+            player.take_step(Some(Terrain::Barrier), 1),
+            StepEffect {
+                damage: 0,
+                healed: 1
+            }
+        );
+        player.hp = player.max_hp();
+        assert_eq!(player.take_step(None, 2), StepEffect::default());
+    }
+
+    #[test]
+    fn test_curse_is_separate_from_necklace_flag() {
+        let mut player = Player::new("ゆうてい");
+        player.items[0] = CursedItem::Belt.id();
+        player.equip_cursed_belt();
+        assert!(player.is_cursed());
+        assert!(player.is_stuck_item(CursedItem::Belt.id()));
+        assert!(!player.is_stuck_item(CursedItem::Necklace.id()));
+        assert!(!player.flags.has_cursed_necklace);
+
+        // しのくびかざり を持っていれば ふっかつのじゅもん の フラグに反映する
+        player.items[1] = DEATH_NECKLACE_ID;
+        let save = decode_from_password_string(&player.to_password_string().unwrap()).unwrap();
+        assert!(save.flags.has_cursed_necklace);
+        assert!(!player.flags.has_cursed_necklace);
+
+        player.lift_curse();
+        assert!(!player.is_cursed());
+        assert_eq!(player.items[0], CursedItem::Belt.id());
+    }
+
+    #[test]
+    fn test_curse_survives_password() {
+        let args = |items| PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(10),
+            items: Some(items),
+            ..Default::default()
+        };
+        let uncursed_max_hp = Player::new_with(args([0; 8])).max_hp();
+        let mut player =
+            Player::new_with(args([CURSED_BELT_ID, DEATH_NECKLACE_ID, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(
+            player.cursed_items(),
+            [CursedItem::Belt, CursedItem::Necklace]
+        );
+
+        // のろいを解いても どうぐ を持ったままなら、じゅもん を入れ直すと また のろわれる
+        player.lift_curse();
+        let loaded = Player::from_password_string(&player.to_password_string().unwrap()).unwrap();
+        assert!(loaded.is_stuck_item(CURSED_BELT_ID));
+        assert!(loaded.is_stuck_item(DEATH_NECKLACE_ID));
+        assert!(loaded.max_hp() < uncursed_max_hp);
+        assert_eq!(loaded.hp, loaded.max_hp());
+    }
 }
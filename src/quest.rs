@@ -0,0 +1,267 @@
+use crate::constants::item_weapon::{
+    ERDRICK_TOKEN_ID, ITEM_MASTER, PRINCESS_LOVE_ID, RAINBOW_DROP_ID, SILVER_HARP_ID,
+    STAFF_OF_RAIN_ID, SUN_STONE_ID,
+};
+use crate::constants::quest::{MILESTONE_LIST, Milestone};
+use crate::inventory::add_item;
+use crate::player::Player;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// ふっかつのじゅもん・フラグ・どうぐ から読み取れる進み具合
+/// にじのはし と りゅうおう はじゅもんに残らないので、探索中の状態から渡す
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuestProgress {
+    done: HashSet<Milestone>,
+}
+
+impl QuestProgress {
+    pub fn new(player: &Player, bridge_built: bool) -> Self {
+        let has = |id: u8| player.items.contains(&id);
+        let mut done = HashSet::new();
+        let mut mark = |milestone: Milestone, is_done: bool| {
+            if is_done {
+                done.insert(milestone);
+            }
+            is_done
+        };
+
+        mark(Milestone::DefeatDragon, player.flags.defeated_dragon);
+        mark(Milestone::RescuePrincess, has(PRINCESS_LOVE_ID));
+        mark(Milestone::FindErdrickToken, has(ERDRICK_TOKEN_ID));
+        mark(Milestone::DefeatGolem, player.flags.defeated_golem);
+        // 交換で手放したどうぐは、後の節目を済ませていれば手に入れたものとみなす
+        let bridge = mark(Milestone::BuildRainbowBridge, bridge_built);
+        let drop = mark(Milestone::GetRainbowDrop, bridge || has(RAINBOW_DROP_ID));
+        mark(Milestone::GetSunStone, drop || has(SUN_STONE_ID));
+        let staff = mark(Milestone::GetStaffOfRain, drop || has(STAFF_OF_RAIN_ID));
+        mark(Milestone::GetSilverHarp, staff || has(SILVER_HARP_ID));
+        if drop {
+            done.insert(Milestone::FindErdrickToken);
+        }
+        Self { done }
+    }
+
+    pub fn from_password(password: &str) -> Result<Self, String> {
+        let player = Player::from_password_string(password)?;
+        Ok(Self::new(&player, false))
+    }
+
+    pub fn is_done(&self, milestone: Milestone) -> bool {
+        self.done.contains(&milestone)
+    }
+
+    pub fn completed(&self) -> Vec<Milestone> {
+        MILESTONE_LIST
+            .iter()
+            .copied()
+            .filter(|m| self.is_done(*m))
+            .collect()
+    }
+
+    /// 次に取りかかれる節目（前提を満たしていて まだ済んでいないもの）
+    pub fn next_steps(&self) -> Vec<Milestone> {
+        MILESTONE_LIST
+            .iter()
+            .copied()
+            .filter(|m| !self.is_done(*m))
+            .filter(|m| m.prerequisites().iter().all(|p| self.is_done(*p)))
+            .collect()
+    }
+
+    /// エンディング（りゅうおうを たおす）までに足りない節目
+    pub fn missing_for_ending(&self) -> Vec<Milestone> {
+        let mut required = HashSet::new();
+        let mut stack = vec![Milestone::DefeatDragonlord];
+        while let Some(milestone) = stack.pop() {
+            if self.is_done(milestone) || !required.insert(milestone) {
+                continue;
+            }
+            stack.extend(milestone.prerequisites());
+        }
+        MILESTONE_LIST
+            .iter()
+            .copied()
+            .filter(|m| required.contains(m))
+            .collect()
+    }
+
+    /// 済んだ節目の割合（%）
+    pub fn percent(&self) -> u8 {
+        (self.done.len() * 100 / MILESTONE_LIST.len()) as u8
+    }
+
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("しんこうど: {}%", self.percent())];
+        for milestone in MILESTONE_LIST.iter() {
+            let mark = if self.is_done(*milestone) {
+                "[x]"
+            } else {
+                "[ ]"
+            };
+            lines.push(format!("{} {}", mark, milestone.as_str()));
+        }
+        lines.push("つぎに できること:".to_string());
+        for milestone in self.next_steps() {
+            lines.push(format!("- {}（{}）", milestone.as_str(), milestone.hint()));
+        }
+        lines
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuestError {
+    MissingItems(Vec<String>),
+    AlreadyHave,
+}
+
+impl fmt::Display for QuestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuestError::MissingItems(items) => {
+                write!(f, "{}が たりない", items.join("・"))
+            }
+            QuestError::AlreadyHave => write!(f, "すでに にじのしずくを もっている"),
+        }
+    }
+}
+
+impl std::error::Error for QuestError {}
+
+/// せいなるほこら で たいようのいし と あまぐものつえ を にじのしずく に替えてもらう
+/// ロトのしるし は見せるだけで手元に残る
+pub fn craft_rainbow_drop(player: &mut Player) -> Result<(), QuestError> {
+    if player.items.contains(&RAINBOW_DROP_ID) {
+        return Err(QuestError::AlreadyHave);
+    }
+    let missing: Vec<String> = [ERDRICK_TOKEN_ID, SUN_STONE_ID, STAFF_OF_RAIN_ID]
+        .iter()
+        .filter(|id| !player.items.contains(id))
+        .map(|&id| ITEM_MASTER[id as usize].name.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(QuestError::MissingItems(missing));
+    }
+    player.remove_item(SUN_STONE_ID);
+    player.remove_item(STAFF_OF_RAIN_ID);
+    // 2つ空いたので必ず入る
+    let _ = add_item(player, RAINBOW_DROP_ID);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::status::Flags;
+    use crate::constants::world::{ERDRICK_TOKEN_POSITION, WORLD_ENTRANCES};
+    use crate::player::PlayerArgs;
+
+    fn player_with(items: [u8; 8], flags: Flags) -> Player {
+        Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            items: Some(items),
+            flags: Some(flags),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_new_game_progress() {
+        let progress = QuestProgress::new(&Player::new("ゆうてい"), false);
+        assert_eq!(progress.percent(), 0);
+        assert!(progress.next_steps().contains(&Milestone::DefeatDragon));
+        assert!(!progress.next_steps().contains(&Milestone::RescuePrincess));
+        assert_eq!(
+            progress.missing_for_ending(),
+            vec![
+                Milestone::FindErdrickToken,
+                Milestone::GetSilverHarp,
+                Milestone::GetStaffOfRain,
+                Milestone::GetSunStone,
+                Milestone::GetRainbowDrop,
+                Milestone::BuildRainbowBridge,
+                Milestone::DefeatDragonlord,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rainbow_drop_implies_traded_items() {
+        let flags = Flags {
+            defeated_dragon: true,
+            ..Default::default()
+        };
+        let progress = QuestProgress::new(&player_with([14, 8, 0, 0, 0, 0, 0, 0], flags), false);
+        for milestone in [
+            Milestone::RescuePrincess,
+            Milestone::FindErdrickToken,
+            Milestone::GetSilverHarp,
+            Milestone::GetStaffOfRain,
+            Milestone::GetSunStone,
+            Milestone::GetRainbowDrop,
+        ] {
+            assert!(progress.is_done(milestone), "{:?}", milestone);
+        }
+        assert_eq!(
+            progress.missing_for_ending(),
+            vec![Milestone::BuildRainbowBridge, Milestone::DefeatDragonlord]
+        );
+        assert!(
+            progress
+                .next_steps()
+                .contains(&Milestone::BuildRainbowBridge)
+        );
+    }
+
+    #[test]
+    fn test_progress_from_password() {
+        let mut player = player_with([7, 12, 0, 0, 0, 0, 0, 0], Flags::default());
+        player.flags.defeated_golem = true;
+        let password = player.to_password_string().unwrap();
+        let progress = QuestProgress::from_password(&password).unwrap();
+        assert_eq!(
+            progress.completed(),
+            vec![
+                Milestone::FindErdrickToken,
+                Milestone::GetSunStone,
+                Milestone::DefeatGolem
+            ]
+        );
+        assert!(QuestProgress::from_password("あ").is_err());
+    }
+
+    #[test]
+    fn test_craft_rainbow_drop() {
+        let mut player = player_with([7, 12, 1, 0, 0, 0, 0, 0], Flags::default());
+        assert_eq!(
+            craft_rainbow_drop(&mut player),
+            Err(QuestError::MissingItems(vec!["あまぐものつえ".to_string()]))
+        );
+        player.items[3] = 13;
+        assert!(craft_rainbow_drop(&mut player).is_ok());
+        assert_eq!(player.items, [7, 1, 14, 0, 0, 0, 0, 0]);
+        assert_eq!(
+            craft_rainbow_drop(&mut player),
+            Err(QuestError::AlreadyHave)
+        );
+    }
+
+    /// ロトのしるし の ヒントの方角が ERDRICK_TOKEN_POSITION と メルキド の位置に あっているか
+    fn erdrick_token_direction() -> String {
+        let town = WORLD_ENTRANCES
+            .iter()
+            .find(|entrance| entrance.name == "メルキド")
+            .unwrap();
+        let (x, y) = ERDRICK_TOKEN_POSITION;
+        let vertical = if y > town.y { "みなみ" } else { "きた" };
+        let horizontal = if x > town.x { "ひがし" } else { "にし" };
+        format!("{}{}", vertical, horizontal)
+    }
+
+    #[test]
+    fn test_erdrick_token_hint_matches_position() {
+        let hint = Milestone::FindErdrickToken.hint();
+        assert!(hint.contains(&format!("メルキドの {}の", erdrick_token_direction())));
+    }
+}
@@ -0,0 +1,359 @@
+use crate::constants::field::{DANGER_HITS, ROUTE_DAMAGE_COST, ROUTE_DANGER_COST};
+use crate::constants::item_weapon::RAINBOW_DROP_ID;
+use crate::constants::world::{RAINBOW_BRIDGE_POSITION, Terrain};
+use crate::encounter::{encounter_rate_at, zone_at, zone_monsters};
+use crate::exploration::{Direction, WalkReport, simulate_walk};
+use crate::monster::Monster;
+use crate::player::Player;
+use crate::world::World;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::South,
+    Direction::East,
+    Direction::West,
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteOptions {
+    pub avoid_damage: bool, // どくのぬまち・バリア を避ける
+    pub avoid_danger: bool, // 勇者のレベルでは危険な区画を避ける
+}
+
+impl RouteOptions {
+    /// avoid-damage / avoid-danger を受け付ける
+    pub fn from_words(words: &[String]) -> Result<Self, String> {
+        let mut options = Self::default();
+        for word in words {
+            match word.to_lowercase().as_str() {
+                "avoid-damage" => options.avoid_damage = true,
+                "avoid-danger" => options.avoid_danger = true,
+                _ => return Err(format!("Invalid route option: {}", word)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    pub path: Vec<(usize, usize)>, // start と goal を含む
+    pub moves: Vec<Direction>,
+    pub cost: u32,
+    pub expected_encounters: f64,
+    pub walk: WalkReport,
+}
+
+impl Route {
+    pub fn start(&self) -> (usize, usize) {
+        self.path[0]
+    }
+
+    pub fn goal(&self) -> (usize, usize) {
+        self.path[self.path.len() - 1]
+    }
+
+    /// Explore Mode の --view にそのまま渡せる形（"n n e ..."）
+    pub fn moves_string(&self) -> String {
+        self.moves
+            .iter()
+            .map(|d| match d {
+                Direction::North => "n",
+                Direction::South => "s",
+                Direction::East => "e",
+                Direction::West => "w",
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// 経路の周り margin マスを描き、道のりを '*'、出発地を '@'、目的地を '!' で重ねる
+    pub fn render_overlay(&self, world: &World, margin: usize) -> Vec<String> {
+        let min_x = self
+            .path
+            .iter()
+            .map(|p| p.0)
+            .min()
+            .unwrap_or(0)
+            .saturating_sub(margin);
+        let min_y = self
+            .path
+            .iter()
+            .map(|p| p.1)
+            .min()
+            .unwrap_or(0)
+            .saturating_sub(margin);
+        let max_x = self.path.iter().map(|p| p.0).max().unwrap_or(0) + margin;
+        let max_y = self.path.iter().map(|p| p.1).max().unwrap_or(0) + margin;
+        world
+            .render_region(min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+            .iter()
+            .enumerate()
+            .map(|(dy, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(dx, c)| {
+                        let position = (min_x + dx, min_y + dy);
+                        if position == self.start() {
+                            '@'
+                        } else if position == self.goal() {
+                            '!'
+                        } else if self.path.contains(&position) {
+                            '*'
+                        } else {
+                            c
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// "x,y" か 入口の名前（WORLD_ENTRANCES）を座標にする
+pub fn parse_location(world: &World, s: &str) -> Result<(usize, usize), String> {
+    if let Some(entrance) = world.find_entrance(s) {
+        return Ok((entrance.x, entrance.y));
+    }
+    let numbers: Vec<usize> = s
+        .split(',')
+        .map(|n| n.trim().parse::<usize>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Unknown location: {}", s))?;
+    match numbers.as_slice() {
+        [x, y] if world.tile_at(*x, *y).is_some() => Ok((*x, *y)),
+        _ => Err(format!("Unknown location: {}", s)),
+    }
+}
+
+/// 区画に 勇者を DANGER_HITS 回の通常攻撃で倒せる敵が出るか
+pub fn is_dangerous_zone(zone: u8, player: &Player) -> bool {
+    zone_monsters(zone).is_some_and(|monsters| {
+        monsters.iter().any(|&id| {
+            let (_, max) = Monster::new(id as usize).battle_attack_range(player);
+            max as u32 * DANGER_HITS >= player.max_hp() as u32
+        })
+    })
+}
+
+/// にじのはし は 地上マップに かかっているか、勇者が にじのしずく を持っていれば渡れるものとする
+fn world_for_route(world: &World, player: &Player) -> World {
+    let mut world = world.clone();
+    let (bx, by) = RAINBOW_BRIDGE_POSITION;
+    if player.items.contains(&RAINBOW_DROP_ID) && world.tile_at(bx, by) == Some(Terrain::Water) {
+        world.set_tile(bx, by, Terrain::Bridge);
+    }
+    world
+}
+
+/// (x, y) へ1歩進むコスト
+fn step_cost(
+    world: &World,
+    player: &Player,
+    x: usize,
+    y: usize,
+    options: &RouteOptions,
+    dangerous: &HashMap<u8, bool>,
+) -> u32 {
+    let mut cost = 1;
+    if options.avoid_damage {
+        let damage = world.tile_at(x, y).map_or(0, |t| player.terrain_damage(t));
+        cost += damage as u32 * ROUTE_DAMAGE_COST;
+    }
+    if options.avoid_danger && zone_at(x, y).is_some_and(|zone| dangerous[&zone]) {
+        cost += ROUTE_DANGER_COST;
+    }
+    cost
+}
+
+/// 地上マップ上で start から goal までの いちばん コストの低い道のりを A* で探す
+pub fn find_route(
+    world: &World,
+    player: &Player,
+    start: (usize, usize),
+    goal: (usize, usize),
+    options: &RouteOptions,
+) -> Result<Route, String> {
+    let world = world_for_route(world, player);
+    if !world.is_walkable(start.0, start.1) || !world.is_walkable(goal.0, goal.1) {
+        return Err(format!("No route from {:?} to {:?}", start, goal));
+    }
+    let mut dangerous = HashMap::new();
+    for y in 0..world.height() {
+        for x in 0..world.width() {
+            if let Some(zone) = zone_at(x, y) {
+                dangerous
+                    .entry(zone)
+                    .or_insert_with(|| is_dangerous_zone(zone, player));
+            }
+        }
+    }
+
+    // コストは1歩 1 以上なので マンハッタン距離が そのまま使える
+    let heuristic = |(x, y): (usize, usize)| (x.abs_diff(goal.0) + y.abs_diff(goal.1)) as u32;
+    let mut open = BinaryHeap::from([Reverse((heuristic(start), 0, start))]);
+    let mut best: HashMap<(usize, usize), u32> = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<(usize, usize), ((usize, usize), Direction)> = HashMap::new();
+
+    while let Some(Reverse((_, cost, position))) = open.pop() {
+        if position == goal {
+            let mut path = vec![goal];
+            let mut moves = Vec::new();
+            let mut current = goal;
+            while let Some(&(previous, direction)) = came_from.get(&current) {
+                path.push(previous);
+                moves.push(direction);
+                current = previous;
+            }
+            path.reverse();
+            moves.reverse();
+            let expected_encounters = path
+                .iter()
+                .skip(1)
+                .map(|&(x, y)| encounter_rate_at(&world, x, y))
+                .sum();
+            let walk = simulate_walk(player, &world, start, &moves);
+            return Ok(Route {
+                path,
+                moves,
+                cost,
+                expected_encounters,
+                walk,
+            });
+        }
+        if best.get(&position).is_some_and(|&b| b < cost) {
+            continue;
+        }
+        for direction in DIRECTIONS {
+            let Some((x, y)) = direction
+                .step_from(position.0, position.1)
+                .filter(|&(x, y)| world.is_walkable(x, y))
+            else {
+                continue;
+            };
+            let next_cost = cost + step_cost(&world, player, x, y, options, &dangerous);
+            if best.get(&(x, y)).is_none_or(|&b| next_cost < b) {
+                best.insert((x, y), next_cost);
+                came_from.insert((x, y), (position, direction));
+                open.push(Reverse((next_cost + heuristic((x, y)), next_cost, (x, y))));
+            }
+        }
+    }
+    Err(format!("No route from {:?} to {:?}", start, goal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exploration::START_POSITION;
+
+    fn route(
+        from: &str,
+        to: &str,
+        options: RouteOptions,
+        player: &Player,
+    ) -> Result<Route, String> {
+        let world = World::new();
+        let start = parse_location(&world, from)?;
+        let goal = parse_location(&world, to)?;
+        find_route(&world, player, start, goal, &options)
+    }
+
+    #[test]
+    fn test_parse_location() {
+        let world = World::new();
+        assert_eq!(parse_location(&world, "ラダトームのしろ"), Ok((43, 43)));
+        assert_eq!(parse_location(&world, "43,44"), Ok(START_POSITION));
+        assert!(parse_location(&world, "999,0").is_err());
+        assert!(parse_location(&world, "どこか").is_err());
+    }
+
+    #[test]
+    fn test_route_is_walkable_and_shortest() {
+        let player = Player::new("ゆうてい");
+        let route = route(
+            "ラダトームのしろ",
+            "ラダトームのまち",
+            RouteOptions::default(),
+            &player,
+        )
+        .unwrap();
+        assert_eq!(route.start(), (43, 43));
+        assert_eq!(route.goal(), (48, 41));
+        // 障害物がなければ マンハッタン距離で着く
+        assert_eq!(route.moves.len(), 7);
+        assert_eq!(route.cost, 7);
+        assert_eq!(route.path.len(), route.moves.len() + 1);
+        assert_eq!(route.walk.steps, 7);
+        assert_eq!(route.walk.blocked, None);
+        assert!(route.expected_encounters > 0.0);
+        assert_eq!(route.moves_string().split(' ').count(), 7);
+
+        let overlay = route.render_overlay(&World::new(), 1);
+        assert!(overlay.iter().any(|line| line.contains('@')));
+        assert!(overlay.iter().any(|line| line.contains('!')));
+    }
+
+    #[test]
+    fn test_avoid_damage() {
+        let player = Player::new("ゆうてい");
+        // ぬまち の北のはしを横切るか 回り道をするか
+        let (from, to) = ("81,108", "86,108");
+        let direct = route(from, to, RouteOptions::default(), &player).unwrap();
+        let options = RouteOptions {
+            avoid_damage: true,
+            ..Default::default()
+        };
+        let careful = route(from, to, options, &player).unwrap();
+        assert_eq!(direct.moves.len(), 5);
+        assert_eq!(direct.walk.damage, 4);
+        assert_eq!(direct.walk.hp_delta(), -4);
+        assert_eq!(careful.walk.damage, 0);
+        assert!(careful.moves.len() > direct.moves.len());
+    }
+
+    #[test]
+    fn test_rainbow_bridge_follows_world_and_player() {
+        // にじのはし が なく にじのしずく も なければ りゅうおうのしろ には行けない
+        let south = "ぬまちのどうくつ（みなみ）";
+        let player = Player::new("ゆうてい");
+        let options = RouteOptions::default();
+        assert!(route(south, "りゅうおうのしろ", options, &player).is_err());
+
+        // にじのしずく を持っていれば はし をかけて渡れる
+        let mut with_drop = player.clone();
+        with_drop.items[0] = RAINBOW_DROP_ID;
+        let bridged = route(south, "りゅうおうのしろ", options, &with_drop).unwrap();
+        assert!(bridged.path.contains(&RAINBOW_BRIDGE_POSITION));
+
+        // すでに かかっている はし は しずく がなくても渡れる
+        let mut world = World::new();
+        let (bx, by) = RAINBOW_BRIDGE_POSITION;
+        world.set_tile(bx, by, Terrain::Bridge);
+        let start = parse_location(&world, south).unwrap();
+        let goal = parse_location(&world, "りゅうおうのしろ").unwrap();
+        let walked = find_route(&world, &player, start, goal, &options).unwrap();
+        assert!(walked.path.contains(&RAINBOW_BRIDGE_POSITION));
+    }
+
+    #[test]
+    fn test_avoid_danger_depends_on_level() {
+        let weak = Player::new("ゆうてい");
+        let mut strong = Player::new("ゆうてい");
+        strong.maximize();
+        assert!(is_dangerous_zone(13, &weak));
+        assert!(!is_dangerous_zone(0, &strong));
+        let options = RouteOptions {
+            avoid_danger: true,
+            ..Default::default()
+        };
+        let weak_route = route("ぬまちのどうくつ（みなみ）", "メルキド", options, &weak).unwrap();
+        let strong_route =
+            route("ぬまちのどうくつ（みなみ）", "メルキド", options, &strong).unwrap();
+        assert!(weak_route.cost > strong_route.cost);
+        assert!(weak_route.moves.len() >= strong_route.moves.len());
+    }
+}
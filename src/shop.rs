@@ -0,0 +1,409 @@
+use crate::constants::item_weapon::{
+    ARMOR_MASTER, CURSED_ITEM_IDS, Equipment, ITEM_MASTER, SHIELD_MASTER, WEAPON_MASTER,
+};
+use crate::constants::town::{HERB_SELL, Town};
+use crate::equipment::{EquipComparison, EquipSlot, StatArrow, compare_equip};
+use crate::inventory::{InventoryError, add_item, can_add};
+use crate::player::Player;
+use crate::traits::message_output::MessageOutput;
+use crate::traits::player_input::PlayerInput;
+use crate::utility::town_utils::{ShopCategory, ShopEntry, shop_entries};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub use crate::constants::item_weapon::{HERB_UNIFIED_ID, KEY_UNIFIED_ID};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShopError {
+    NotForSale,
+    NotEnoughGold { price: u16, gold: u16 },
+    InventoryFull,
+    HerbLimit,
+    KeyLimit,
+    AlreadyEquipped,
+    NotOwned,
+    Cursed,
+    NotSellable,
+}
+
+impl fmt::Display for ShopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShopError::NotForSale => write!(f, "そのしなものは あつかっていない"),
+            ShopError::NotEnoughGold { price, gold } => {
+                write!(f, "おかねが たりない（{}G ひつよう / {}G）", price, gold)
+            }
+            ShopError::InventoryFull => write!(f, "もちものが いっぱいだ"),
+            ShopError::HerbLimit => write!(f, "やくそうは これいじょう もてない"),
+            ShopError::KeyLimit => write!(f, "かぎは これいじょう もてない"),
+            ShopError::AlreadyEquipped => write!(f, "すでに そうびしている"),
+            ShopError::NotOwned => write!(f, "そのしなものを もっていない"),
+            ShopError::Cursed => write!(f, "のろわれていて てばなせない"),
+            ShopError::NotSellable => write!(f, "それを かいとることは できない"),
+        }
+    }
+}
+
+impl std::error::Error for ShopError {}
+
+impl From<InventoryError> for ShopError {
+    fn from(error: InventoryError) -> Self {
+        match error {
+            InventoryError::InventoryFull => ShopError::InventoryFull,
+            InventoryError::HerbLimit => ShopError::HerbLimit,
+            InventoryError::KeyLimit => ShopError::KeyLimit,
+            InventoryError::NotOwned => ShopError::NotOwned,
+            InventoryError::Cursed => ShopError::Cursed,
+            _ => ShopError::NotForSale,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TradeIn {
+    pub name: String,
+    pub sell: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Purchase {
+    pub name: String,
+    pub price: u16,
+    pub trade_in: Option<TradeIn>, // ぶき・よろい・たて の下取り
+    pub gold: u16,                 // 取引後の所持金
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sale {
+    pub name: String,
+    pub sell: u16,
+    pub gold: u16,
+}
+
+impl Purchase {
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!("{}を {}ゴールドで かった", self.name, self.price)];
+        if let Some(trade_in) = &self.trade_in {
+            lines.push(format!(
+                "{}を {}ゴールドで ひきとった",
+                trade_in.name, trade_in.sell
+            ));
+        }
+        lines
+    }
+}
+
+impl Sale {
+    pub fn message_lines(&self) -> Vec<String> {
+        vec![format!("{}を {}ゴールドで うった", self.name, self.sell)]
+    }
+}
+
+fn pay(player: &mut Player, price: u16) -> Result<(), ShopError> {
+    if player.gold < price {
+        return Err(ShopError::NotEnoughGold {
+            price,
+            gold: player.gold,
+        });
+    }
+    player.gold -= price;
+    Ok(())
+}
+
+fn equipment_slot(
+    player: &mut Player,
+    category: ShopCategory,
+) -> Option<(&mut u8, &'static [Equipment])> {
+    match category {
+        ShopCategory::Weapon => Some((&mut player.weapon, &WEAPON_MASTER)),
+        ShopCategory::Armor => Some((&mut player.armor, &ARMOR_MASTER)),
+        ShopCategory::Shield => Some((&mut player.shield, &SHIELD_MASTER)),
+        _ => None,
+    }
+}
+
+/// ぶき・よろい・たて を買ったときの つよさの くらべ（それ以外は None）
+pub fn compare_entry(player: &Player, entry: &ShopEntry) -> Option<EquipComparison> {
+    let slot = match entry.category {
+        ShopCategory::Weapon => EquipSlot::Weapon,
+        ShopCategory::Armor => EquipSlot::Armor,
+        ShopCategory::Shield => EquipSlot::Shield,
+        _ => return None,
+    };
+    compare_equip(player, slot, entry.id).ok()
+}
+
+/// 店の画面に出す矢印（ぶき は こうげきりょく、よろい・たて は しゅびりょく）
+pub fn entry_arrow(player: &Player, entry: &ShopEntry) -> StatArrow {
+    match (entry.category, compare_entry(player, entry)) {
+        (ShopCategory::Weapon, Some(comparison)) => comparison.attack_arrow,
+        (_, Some(comparison)) => comparison.defense_arrow,
+        (_, None) => StatArrow::Same,
+    }
+}
+
+/// 商品を買う。ぶき・よろい・たて は今の装備を売値で下取りする
+pub fn buy(player: &mut Player, entry: &ShopEntry) -> Result<Purchase, ShopError> {
+    match entry.category {
+        ShopCategory::Weapon | ShopCategory::Armor | ShopCategory::Shield => {
+            let current = equipment_slot(player, entry.category).map(|(slot, _)| *slot);
+            if current == Some(entry.id) {
+                return Err(ShopError::AlreadyEquipped);
+            }
+            pay(player, entry.price)?;
+            let (slot, master) = equipment_slot(player, entry.category).unwrap();
+            let old = &master[*slot as usize];
+            *slot = entry.id;
+            let trade_in = (old.price > 0).then(|| TradeIn {
+                name: old.name.to_string(),
+                sell: old.sell,
+            });
+            if let Some(trade_in) = &trade_in {
+                player.gold = player.gold.saturating_add(trade_in.sell);
+            }
+            Ok(Purchase {
+                name: entry.name.clone(),
+                price: entry.price,
+                trade_in,
+                gold: player.gold,
+            })
+        }
+        ShopCategory::Herb | ShopCategory::Key | ShopCategory::Item => {
+            let id = match entry.category {
+                ShopCategory::Herb => HERB_UNIFIED_ID,
+                ShopCategory::Key => KEY_UNIFIED_ID,
+                _ => entry.id,
+            };
+            can_add(player, id)?;
+            pay(player, entry.price)?;
+            add_item(player, id)?;
+            Ok(purchase(player, entry))
+        }
+    }
+}
+
+fn purchase(player: &Player, entry: &ShopEntry) -> Purchase {
+    Purchase {
+        name: entry.name.clone(),
+        price: entry.price,
+        trade_in: None,
+        gold: player.gold,
+    }
+}
+
+/// 町の品揃えの index 番目（0 始まり）を買う
+pub fn buy_from_town(
+    player: &mut Player,
+    town: &Town,
+    index: usize,
+) -> Result<Purchase, ShopError> {
+    let entries = shop_entries(town);
+    let entry = entries.get(index).ok_or(ShopError::NotForSale)?;
+    buy(player, entry)
+}
+
+/// どうぐを売る。id は unified_item_list の ID（やくそう 101、かぎ 102、それ以外は ITEM_MASTER）
+pub fn sell(player: &mut Player, id: u8) -> Result<Sale, ShopError> {
+    match id {
+        HERB_UNIFIED_ID => {
+            if player.herbs == 0 {
+                return Err(ShopError::NotOwned);
+            }
+            player.herbs -= 1;
+            player.gold = player.gold.saturating_add(HERB_SELL);
+            Ok(Sale {
+                name: "やくそう".to_string(),
+                sell: HERB_SELL,
+                gold: player.gold,
+            })
+        }
+        KEY_UNIFIED_ID => Err(ShopError::NotSellable),
+        _ => {
+            let Some(slot) = player
+                .items
+                .iter()
+                .position(|&item| item != 0 && item == id)
+            else {
+                return Err(ShopError::NotOwned);
+            };
+            if CURSED_ITEM_IDS.contains(&id) {
+                return Err(ShopError::Cursed);
+            }
+            let item = &ITEM_MASTER[id as usize];
+            if item.sell == 0 {
+                return Err(ShopError::NotSellable);
+            }
+            player.items[slot] = 0;
+            player.gold = player.gold.saturating_add(item.sell);
+            Ok(Sale {
+                name: item.name.to_string(),
+                sell: item.sell,
+                gold: player.gold,
+            })
+        }
+    }
+}
+
+/// 対話形式の買い物（0: でる）
+pub fn run_shop(
+    player: &mut Player,
+    town: &Town,
+    input: &mut dyn PlayerInput,
+    output: &mut dyn MessageOutput,
+) {
+    let entries = shop_entries(town);
+    if entries.is_empty() {
+        output.output("この まちには みせが ない");
+        return;
+    }
+    loop {
+        output.output(&format!("--- {} のみせ ---", town.name));
+        output.output(&format!("ゴールド: {}", player.gold));
+        output.output("0: でる");
+        for (i, entry) in entries.iter().enumerate() {
+            output.output(&format!(
+                "{}: {} {}G{}",
+                i + 1,
+                entry.name,
+                entry.price,
+                entry_arrow(player, entry).symbol()
+            ));
+        }
+        let selected = input.get_player_input(entries.len());
+        if selected == 0 {
+            output.output("また どうぞ");
+            return;
+        }
+        match buy(player, &entries[selected - 1]) {
+            Ok(purchase) => {
+                for line in purchase.message_lines() {
+                    output.output(&line);
+                }
+            }
+            Err(error) => output.output(&error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::item_weapon::{MAX_HERBS, MAX_KEYS};
+    use crate::constants::town::TOWN_MASTER;
+    use crate::player::PlayerArgs;
+
+    fn player_with_gold(gold: u16) -> Player {
+        Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            gold: Some(gold),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_buy_weapon_trades_in_old_one() {
+        let mut player = player_with_gold(200);
+        player.weapon = 1; // たけざお（売値 5）
+        // ラダトームのまち: どうのつるぎ（180G）
+        let purchase = buy_from_town(&mut player, &TOWN_MASTER[1], 2).unwrap();
+        assert_eq!(purchase.name, "どうのつるぎ");
+        assert_eq!(purchase.trade_in.unwrap().name, "たけざお");
+        assert_eq!(player.weapon, 3);
+        assert_eq!(player.gold, 200 - 180 + WEAPON_MASTER[1].sell);
+
+        assert_eq!(
+            buy_from_town(&mut player, &TOWN_MASTER[1], 2),
+            Err(ShopError::AlreadyEquipped)
+        );
+    }
+
+    #[test]
+    fn test_buy_requires_gold() {
+        let mut player = player_with_gold(5);
+        let result = buy_from_town(&mut player, &TOWN_MASTER[1], 2);
+        assert_eq!(
+            result,
+            Err(ShopError::NotEnoughGold {
+                price: 180,
+                gold: 5
+            })
+        );
+        assert_eq!(player.weapon, 0);
+        assert_eq!(player.gold, 5);
+    }
+
+    #[test]
+    fn test_buy_respects_caps_and_slots() {
+        let mut player = player_with_gold(10000);
+        let herb = ShopEntry {
+            category: ShopCategory::Herb,
+            id: 0,
+            name: "やくそう".to_string(),
+            price: 24,
+        };
+        player.herbs = MAX_HERBS;
+        assert_eq!(buy(&mut player, &herb), Err(ShopError::HerbLimit));
+
+        let key = ShopEntry {
+            category: ShopCategory::Key,
+            id: 0,
+            name: "まほうのかぎ".to_string(),
+            price: 53,
+        };
+        player.keys = MAX_KEYS - 1;
+        assert!(buy(&mut player, &key).is_ok());
+        assert_eq!(buy(&mut player, &key), Err(ShopError::KeyLimit));
+
+        let torch = ShopEntry {
+            category: ShopCategory::Item,
+            id: 1,
+            name: "たいまつ".to_string(),
+            price: 8,
+        };
+        for _ in 0..8 {
+            assert!(buy(&mut player, &torch).is_ok());
+        }
+        assert_eq!(buy(&mut player, &torch), Err(ShopError::InventoryFull));
+    }
+
+    #[test]
+    fn test_sell_rules() {
+        let mut player = player_with_gold(0);
+        player.items = [1, 9, 7, 0, 0, 0, 0, 0];
+        player.herbs = 1;
+        player.keys = 1;
+
+        assert_eq!(sell(&mut player, 1).unwrap().sell, ITEM_MASTER[1].sell);
+        assert_eq!(player.items[0], 0);
+        assert_eq!(sell(&mut player, 9), Err(ShopError::Cursed));
+        assert_eq!(sell(&mut player, 7), Err(ShopError::NotSellable));
+        assert_eq!(
+            sell(&mut player, KEY_UNIFIED_ID),
+            Err(ShopError::NotSellable)
+        );
+        assert_eq!(sell(&mut player, 3), Err(ShopError::NotOwned));
+        assert!(sell(&mut player, HERB_UNIFIED_ID).is_ok());
+        assert_eq!(sell(&mut player, HERB_UNIFIED_ID), Err(ShopError::NotOwned));
+        assert_eq!(player.gold, ITEM_MASTER[1].sell + HERB_SELL);
+    }
+
+    #[test]
+    fn test_entry_arrows() {
+        let mut player = player_with_gold(0);
+        player.weapon = 2; // こんぼう
+        let entries = shop_entries(&TOWN_MASTER[1]);
+        let arrows: Vec<StatArrow> = entries
+            .iter()
+            .filter(|entry| entry.category == ShopCategory::Weapon)
+            .map(|entry| entry_arrow(&player, entry))
+            .collect();
+        assert_eq!(
+            arrows[..3],
+            [StatArrow::Down, StatArrow::Same, StatArrow::Up]
+        );
+        let herb = entries
+            .iter()
+            .find(|entry| entry.category == ShopCategory::Herb);
+        assert!(herb.is_none_or(|entry| compare_entry(&player, entry).is_none()));
+    }
+}
@@ -0,0 +1,544 @@
+use crate::calculator::{AmountProbability, monster_action, monster_attack, player_attack};
+use crate::constants::config::BIT_8_MAX;
+use crate::constants::monster::{ActionType, MONSTER_MASTER, MonsterAction};
+use crate::constants::spell::Spell;
+use crate::monster::Monster;
+use crate::player::{Player, PlayerArgs};
+use crate::utility::random_utils::success_rate_by_percent;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+/// 集計を打ち切るラウンド数
+pub const MAX_ROUNDS: u32 = 100;
+/// 推奨レベルの基準となる安全率
+pub const SAFETY_THRESHOLD: f64 = 0.95;
+pub const MAX_LEVEL: u8 = 30;
+
+/// これより小さい確率の状態は切り捨てる
+const PRUNE_PROBABILITY: f64 = 1e-10;
+/// 敵が逃げ出す確率（勇者の力 / 2 >= 敵の攻撃力 の時）
+const MONSTER_ESCAPE_RATE: f64 = 0.25;
+/// 眠りから覚める確率
+const WAKE_UP_PERCENT: f64 = 33.33;
+/// マホトーンの成功率
+const SEAL_PERCENT: f64 = 50.0;
+
+/// 1対1の戦闘を「勇者は毎ターンこうげき」として解析した結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurvivalReport {
+    pub level: u8,
+    pub monster_id: usize,
+    pub monster_name: String,
+    pub win_rate: f64,
+    pub death_rate: f64,
+    pub monster_escape_rate: f64,
+    pub undecided_rate: f64,  // MAX_ROUNDS で決着しなかった確率
+    pub expected_rounds: f64, // 勝利した時の平均ラウンド数
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecommendedLevel {
+    pub monster_id: usize,
+    pub monster_name: String,
+    pub level: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurvivalMatrix {
+    pub reports: Vec<SurvivalReport>,
+    pub recommended_levels: Vec<RecommendedLevel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BattleNode {
+    player_hp: u8,
+    monster_hp: u8,
+    asleep: bool,
+    sealed: bool,
+}
+
+#[derive(Debug, Clone)]
+enum MonsterOutcome {
+    Escape,
+    PlayerDamage(DamageTable),
+    MonsterHeal(Vec<AmountProbability>),
+    Sleep,
+    Seal(f64),
+}
+
+impl SurvivalReport {
+    pub fn safety_rate(&self) -> f64 {
+        1.0 - self.death_rate
+    }
+
+    pub fn csv_header() -> &'static str {
+        "level,monster_id,monster_name,win_rate,death_rate,monster_escape_rate,undecided_rate,expected_rounds"
+    }
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{:.6},{:.6},{:.6},{:.6},{:.3}",
+            self.level,
+            self.monster_id,
+            self.monster_name,
+            self.win_rate,
+            self.death_rate,
+            self.monster_escape_rate,
+            self.undecided_rate,
+            self.expected_rounds
+        )
+    }
+}
+
+impl SurvivalMatrix {
+    pub fn to_csv(&self) -> String {
+        std::iter::once(SurvivalReport::csv_header().to_string())
+            .chain(self.reports.iter().map(|r| r.to_csv_row()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+/// ダメージ分布と、あるダメージ以上になる確率の累積
+#[derive(Debug, Clone)]
+struct DamageTable {
+    outcomes: Vec<AmountProbability>,
+    at_least: Vec<f64>,
+}
+
+impl DamageTable {
+    fn new(outcomes: Vec<AmountProbability>) -> Self {
+        let mut at_least = vec![0.0; outcomes.len() + 1];
+        for i in (0..outcomes.len()).rev() {
+            at_least[i] = at_least[i + 1] + outcomes[i].probability;
+        }
+        Self { outcomes, at_least }
+    }
+
+    /// HP が 0 になる確率と、倒れない側のダメージ一覧
+    fn split(&self, hp: u8) -> (f64, &[AmountProbability]) {
+        let lethal_from = self.outcomes.partition_point(|o| o.amount < hp);
+        (self.at_least[lethal_from], &self.outcomes[..lethal_from])
+    }
+}
+
+/// (勇者HP, 敵HP, 眠り, 封印) ごとの確率を密な配列で集計する
+struct StateGrid {
+    player_size: usize,
+    monster_size: usize,
+    cells: Vec<f64>,
+    active: Vec<usize>,
+}
+
+impl StateGrid {
+    fn new(player_max_hp: u8, monster_max_hp: u8) -> Self {
+        let player_size = player_max_hp as usize + 1;
+        let monster_size = monster_max_hp as usize + 1;
+        Self {
+            player_size,
+            monster_size,
+            cells: vec![0.0; player_size * monster_size * 4],
+            active: Vec::new(),
+        }
+    }
+
+    fn index(&self, node: BattleNode) -> usize {
+        let flags = (node.asleep as usize) << 1 | node.sealed as usize;
+        (flags * self.player_size + node.player_hp as usize) * self.monster_size
+            + node.monster_hp as usize
+    }
+
+    fn node(&self, index: usize) -> BattleNode {
+        let monster_hp = index % self.monster_size;
+        let rest = index / self.monster_size;
+        let player_hp = rest % self.player_size;
+        let flags = rest / self.player_size;
+        BattleNode {
+            player_hp: player_hp as u8,
+            monster_hp: monster_hp as u8,
+            asleep: flags & 0b10 != 0,
+            sealed: flags & 0b01 != 0,
+        }
+    }
+
+    fn add(&mut self, node: BattleNode, probability: f64) {
+        if probability <= 0.0 {
+            return;
+        }
+        let index = self.index(node);
+        if self.cells[index] == 0.0 {
+            self.active.push(index);
+        }
+        self.cells[index] += probability;
+    }
+
+    /// 集計済みの状態を取り出して配列を空に戻す
+    fn drain(&mut self) -> Vec<(BattleNode, f64)> {
+        let active = std::mem::take(&mut self.active);
+        active
+            .into_iter()
+            .filter_map(|index| {
+                let probability = std::mem::take(&mut self.cells[index]);
+                (probability > PRUNE_PROBABILITY).then(|| (self.node(index), probability))
+            })
+            .collect()
+    }
+}
+
+/// choose_action と同じく、先頭から順に発動率で抽選した時の各候補の選択確率
+fn choose_action_rates(candidates: &[MonsterAction]) -> Vec<(ActionType, f64)> {
+    let mut remaining = 1.0;
+    candidates
+        .iter()
+        .map(|candidate| {
+            let rate = candidate.rate as f64 / 100.0;
+            let chosen = remaining * rate;
+            remaining *= 1.0 - rate;
+            (candidate.action, chosen)
+        })
+        .collect()
+}
+
+/// Battle::decide_enemy_action を確率として展開する
+fn monster_outcomes(
+    monster: &Monster,
+    player: &Player,
+    low_hp: bool,
+    asleep: bool,
+    sealed: bool,
+) -> Vec<(f64, MonsterOutcome)> {
+    let mut outcomes = Vec::new();
+    let mut remaining = 1.0;
+
+    if player.strength() / 2 >= monster.stats.attack {
+        outcomes.push((MONSTER_ESCAPE_RATE, MonsterOutcome::Escape));
+        remaining -= MONSTER_ESCAPE_RATE;
+    }
+
+    let mut fallthrough = remaining;
+    if monster.has_support_magic() {
+        for (action, rate) in choose_action_rates(&monster.support_spells_actions()) {
+            let probability = remaining * rate;
+            let outcome = match action {
+                ActionType::Spell(Spell::Hoimi) | ActionType::Spell(Spell::Behoimi) if low_hp => {
+                    monster_action(player, &action)
+                        .map(|report| MonsterOutcome::MonsterHeal(report.distribution))
+                }
+                ActionType::Spell(Spell::Rarirho) if !asleep => Some(MonsterOutcome::Sleep),
+                ActionType::Spell(Spell::Mahoton) if !sealed => {
+                    let success = if player.is_max_armor() {
+                        0.0
+                    } else {
+                        success_rate_by_percent(SEAL_PERCENT)
+                    };
+                    Some(MonsterOutcome::Seal(success))
+                }
+                _ => None,
+            };
+            if let Some(outcome) = outcome {
+                outcomes.push((probability, outcome));
+                fallthrough -= probability;
+            }
+        }
+    }
+
+    let mut attack_rate = fallthrough;
+    if monster.has_attack_skill() {
+        for (action, rate) in choose_action_rates(&monster.attack_spells_actions()) {
+            let probability = fallthrough * rate;
+            if let Some(report) = monster_action(player, &action) {
+                outcomes.push((
+                    probability,
+                    MonsterOutcome::PlayerDamage(DamageTable::new(report.distribution)),
+                ));
+                attack_rate -= probability;
+            }
+        }
+    }
+
+    let attack = monster_attack(monster, player);
+    outcomes.push((
+        attack_rate,
+        MonsterOutcome::PlayerDamage(DamageTable::new(attack.distribution)),
+    ));
+    outcomes
+}
+
+/// 敵の初期HP（Monster::new の HP 減少ロール）の分布
+fn initial_monster_hp(monster: &Monster) -> HashMap<u8, f64> {
+    let mut distribution = HashMap::new();
+    let per_rand = 1.0 / (BIT_8_MAX as f64 + 1.0);
+    for rand_val in 0..=BIT_8_MAX {
+        let reduction = (monster.stats.hp as u16 * rand_val as u16) / 1024;
+        let hp = monster.stats.hp.saturating_sub(reduction as u8);
+        *distribution.entry(hp).or_insert(0.0) += per_rand;
+    }
+    distribution
+}
+
+fn is_low_hp(monster: &Monster, hp: u8) -> bool {
+    (hp as f32) <= (monster.stats.hp as f32 / 4.0)
+}
+
+/// 勇者が毎ターンこうげきした時の勝率・死亡率・平均ラウンド数
+pub fn analyze_survival(player: &Player, monster: &Monster) -> SurvivalReport {
+    let hero_attack = DamageTable::new(player_attack(player, monster).distribution);
+    let wake_up_rate = success_rate_by_percent(WAKE_UP_PERCENT);
+
+    let mut outcome_cache: HashMap<(bool, bool, bool), Vec<(f64, MonsterOutcome)>> = HashMap::new();
+    let mut grid = StateGrid::new(player.hp, monster.max_hp());
+    for (monster_hp, probability) in initial_monster_hp(monster) {
+        let node = BattleNode {
+            player_hp: player.hp,
+            monster_hp,
+            asleep: false,
+            sealed: false,
+        };
+        grid.add(node, probability);
+    }
+    let mut states = grid.drain();
+
+    let mut win_rate = 0.0;
+    let mut death_rate = 0.0;
+    let mut monster_escape_rate = 0.0;
+    let mut win_rounds = 0.0;
+
+    for round in 1..=MAX_ROUNDS {
+        // 勇者のターン
+        for &(node, probability) in &states {
+            let awake_probability = if node.asleep {
+                let still_asleep = probability * (1.0 - wake_up_rate);
+                grid.add(node, still_asleep);
+                probability - still_asleep
+            } else {
+                probability
+            };
+
+            let (lethal, survived) = hero_attack.split(node.monster_hp);
+            win_rate += awake_probability * lethal;
+            win_rounds += awake_probability * lethal * round as f64;
+            for damage in survived {
+                let next = BattleNode {
+                    monster_hp: node.monster_hp - damage.amount,
+                    asleep: false,
+                    ..node
+                };
+                grid.add(next, awake_probability * damage.probability);
+            }
+        }
+        states = grid.drain();
+
+        // 敵のターン
+        for &(node, probability) in &states {
+            let key = (
+                is_low_hp(monster, node.monster_hp),
+                node.asleep,
+                node.sealed,
+            );
+            let outcomes = outcome_cache
+                .entry(key)
+                .or_insert_with(|| monster_outcomes(monster, player, key.0, key.1, key.2));
+
+            for (rate, outcome) in outcomes.iter() {
+                let p = probability * rate;
+                match outcome {
+                    MonsterOutcome::Escape => monster_escape_rate += p,
+                    MonsterOutcome::PlayerDamage(table) => {
+                        let (lethal, survived) = table.split(node.player_hp);
+                        death_rate += p * lethal;
+                        for damage in survived {
+                            let player_hp = node.player_hp - damage.amount;
+                            grid.add(BattleNode { player_hp, ..node }, p * damage.probability);
+                        }
+                    }
+                    MonsterOutcome::MonsterHeal(distribution) => {
+                        for heal in distribution {
+                            let monster_hp = (node.monster_hp as u16 + heal.amount as u16)
+                                .min(monster.max_hp() as u16)
+                                as u8;
+                            grid.add(BattleNode { monster_hp, ..node }, p * heal.probability);
+                        }
+                    }
+                    MonsterOutcome::Sleep => {
+                        let next = BattleNode {
+                            asleep: true,
+                            ..node
+                        };
+                        grid.add(next, p);
+                    }
+                    MonsterOutcome::Seal(success) => {
+                        let next = BattleNode {
+                            sealed: true,
+                            ..node
+                        };
+                        grid.add(next, p * success);
+                        grid.add(node, p * (1.0 - success));
+                    }
+                }
+            }
+        }
+        states = grid.drain();
+        if states.is_empty() {
+            break;
+        }
+    }
+
+    SurvivalReport {
+        level: player.level(),
+        monster_id: monster.id as usize,
+        monster_name: monster.name().to_string(),
+        win_rate,
+        death_rate,
+        monster_escape_rate,
+        undecided_rate: states.iter().fold(0.0, |total, (_, p)| total + p),
+        expected_rounds: if win_rate > 0.0 {
+            win_rounds / win_rate
+        } else {
+            0.0
+        },
+    }
+}
+
+/// 名前・装備・フラグはそのままに、指定レベルの勇者（HP/MP 満タン）を作る
+pub fn player_at_level(base: &Player, level: u8) -> Player {
+    Player::new_with(PlayerArgs {
+        name: Some(base.name.clone()),
+        level: Some(level),
+        gold: Some(base.gold),
+        weapon: Some(base.weapon),
+        armor: Some(base.armor),
+        shield: Some(base.shield),
+        items: Some(base.items),
+        herbs: Some(base.herbs),
+        keys: Some(base.keys),
+        flags: Some(base.flags.clone()),
+        ..Default::default()
+    })
+}
+
+/// レベル 1～30 × 指定モンスターの解析結果と、安全率 95% 以上となる最低レベル
+pub fn analyze_survival_matrix(base: &Player, monster_ids: &[usize]) -> SurvivalMatrix {
+    analyze_survival_matrix_for_levels(base, monster_ids, 1..=MAX_LEVEL)
+}
+
+/// levels の範囲だけを解析する（範囲内に安全なレベルがなければ推奨レベルは None）
+pub fn analyze_survival_matrix_for_levels(
+    base: &Player,
+    monster_ids: &[usize],
+    levels: RangeInclusive<u8>,
+) -> SurvivalMatrix {
+    let players: Vec<Player> = levels.map(|level| player_at_level(base, level)).collect();
+
+    let mut reports = Vec::new();
+    let mut recommended_levels = Vec::new();
+    for &monster_id in monster_ids {
+        let monster = Monster::new(monster_id);
+        let monster_reports: Vec<SurvivalReport> = players
+            .iter()
+            .map(|player| analyze_survival(player, &monster))
+            .collect();
+
+        recommended_levels.push(RecommendedLevel {
+            monster_id,
+            monster_name: monster.name().to_string(),
+            level: monster_reports
+                .iter()
+                .find(|r| r.safety_rate() >= SAFETY_THRESHOLD)
+                .map(|r| r.level),
+        });
+        reports.extend(monster_reports);
+    }
+
+    SurvivalMatrix {
+        reports,
+        recommended_levels,
+    }
+}
+
+pub fn all_monster_ids() -> Vec<usize> {
+    (0..MONSTER_MASTER.len()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(report: &SurvivalReport) -> f64 {
+        report.win_rate + report.death_rate + report.monster_escape_rate + report.undecided_rate
+    }
+
+    #[test]
+    fn test_initial_monster_hp_distribution() {
+        // ゴーレム HP70 → 70 - (70 * 255) / 1024 = 53 ～ 70
+        let distribution = initial_monster_hp(&Monster::new(32));
+        let min = distribution.keys().min().copied().unwrap();
+        let max = distribution.keys().max().copied().unwrap();
+        assert_eq!((min, max), (53, 70));
+        assert!((distribution.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_choose_action_rates_in_order() {
+        let candidates = [
+            MonsterAction {
+                ab_type: "A",
+                action: ActionType::Spell(Spell::Rarirho),
+                rate: 25,
+            },
+            MonsterAction {
+                ab_type: "B",
+                action: ActionType::Spell(Spell::Gira),
+                rate: 50,
+            },
+        ];
+        let rates = choose_action_rates(&candidates);
+        assert_eq!(rates[0].1, 0.25);
+        assert_eq!(rates[1].1, 0.375);
+    }
+
+    #[test]
+    fn test_level_1_beats_slime_safely() {
+        let report = analyze_survival(&Player::new("ゆうてい"), &Monster::new(0));
+        assert!((total(&report) - 1.0).abs() < 1e-6);
+        assert!(report.safety_rate() >= SAFETY_THRESHOLD);
+        assert!(report.expected_rounds >= 1.0);
+    }
+
+    #[test]
+    fn test_level_1_dies_to_dragonlord() {
+        let report = analyze_survival(&Player::new("ゆうてい"), &Monster::new(39));
+        assert!(report.death_rate > 0.99);
+        assert_eq!(report.win_rate, 0.0);
+    }
+
+    #[test]
+    fn test_sleeping_caster_probabilities_sum_to_one() {
+        // ラリホーを使う敵（メイジキメラ）でも確率の合計は 1（低いレベルなら 状態が少なく すぐ終わる）
+        let player = Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(3),
+            ..Default::default()
+        });
+        let report = analyze_survival(&player, &Monster::new(26));
+        assert!((total(&report) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_recommended_level_increases_with_monster_strength() {
+        const LEVELS: u8 = 2;
+        // スライム と スライムベス なら レベル1～2 だけで 推奨レベルが決まる
+        let matrix =
+            analyze_survival_matrix_for_levels(&Player::new("ゆうてい"), &[0, 1], 1..=LEVELS);
+        assert_eq!(matrix.reports.len(), 2 * LEVELS as usize);
+        let slime = matrix.recommended_levels[0].level.unwrap();
+        let slime_beth = matrix.recommended_levels[1].level.unwrap();
+        assert_eq!(slime, 1);
+        assert!(slime_beth > slime);
+        assert!(matrix.to_csv().lines().count() == 1 + 2 * LEVELS as usize);
+    }
+}
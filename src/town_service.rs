@@ -0,0 +1,240 @@
+use crate::constants::town::Town;
+use crate::player::Player;
+use crate::utility::status_utils::get_status_by_level;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceError {
+    NoInn,
+    NotEnoughGold { price: u16, gold: u16 },
+    NotCursed,
+    Password(String),
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceError::NoInn => write!(f, "この まちには やどやが ない"),
+            ServiceError::NotEnoughGold { price, gold } => {
+                write!(f, "おかねが たりない（{}G ひつよう / {}G）", price, gold)
+            }
+            ServiceError::NotCursed => write!(f, "そなたは のろわれて おらぬようじゃ"),
+            ServiceError::Password(message) => {
+                write!(f, "ふっかつのじゅもんを つくれない: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+/// 町で受けられるサービス
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TownService {
+    Inn,
+    King,
+    Curse,
+}
+
+impl FromStr for TownService {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "inn" | "やどや" => Ok(TownService::Inn),
+            "king" | "おうさま" => Ok(TownService::King),
+            "curse" | "church" | "のろい" => Ok(TownService::Curse),
+            _ => Err(format!("不明なサービスです: {}", input)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InnStay {
+    pub town: String,
+    pub price: u16,
+    pub hp: u8,
+    pub mp: u8,
+    pub gold: u16, // 支払い後の所持金
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KingSave {
+    pub name: String,
+    pub level: u8,
+    pub exp: u16,
+    pub next_exp: Option<u16>, // つぎのレベルまでの経験値（レベル30なら None）
+    pub password: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurseRemoval {
+    pub removed: Vec<String>,
+}
+
+impl InnStay {
+    pub fn message_lines(&self) -> Vec<String> {
+        vec![
+            format!("{}の やどやに {}ゴールドで とまった", self.town, self.price),
+            "おはよう ございます".to_string(),
+            format!("HP {} MP {} に かいふくした", self.hp, self.mp),
+        ]
+    }
+}
+
+impl KingSave {
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        match self.next_exp {
+            Some(next_exp) => lines.push(format!(
+                "{}が つぎのレベルになるには あと {}ポイントの けいけんが ひつようじゃ",
+                self.name, next_exp
+            )),
+            None => lines.push(format!("{}は もう じゅうぶんに つよい", self.name)),
+        }
+        lines.push("そなたに ふっかつのじゅもんを おしえよう".to_string());
+        lines.push(self.password.clone());
+        lines.push("これを わすれぬよう かきとめておくのじゃ".to_string());
+        lines
+    }
+}
+
+impl CurseRemoval {
+    pub fn message_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .removed
+            .iter()
+            .map(|name| format!("{}を はずした", name))
+            .collect();
+        lines.push("そなたの のろいは とけた".to_string());
+        lines
+    }
+}
+
+/// 宿屋に泊まり HP・MP を最大まで回復する
+pub fn stay_at_inn(player: &mut Player, town: &Town) -> Result<InnStay, ServiceError> {
+    let price = town.inn_price.ok_or(ServiceError::NoInn)?;
+    if player.gold < price {
+        return Err(ServiceError::NotEnoughGold {
+            price,
+            gold: player.gold,
+        });
+    }
+    player.gold -= price;
+    player.hp_maximize();
+    player.mp_maximize();
+    Ok(InnStay {
+        town: town.name.to_string(),
+        price,
+        hp: player.hp,
+        mp: player.mp,
+        gold: player.gold,
+    })
+}
+
+/// つぎのレベルまでに必要な経験値
+pub fn next_level_exp(player: &Player) -> Option<u16> {
+    get_status_by_level(player.level() + 1).map(|s| s.required_exp.saturating_sub(player.exp))
+}
+
+/// 王様に話しかけて ふっかつのじゅもん を聞く
+pub fn talk_to_king(player: &Player) -> Result<KingSave, ServiceError> {
+    let password = player
+        .to_password_string()
+        .map_err(ServiceError::Password)?;
+    Ok(KingSave {
+        name: player.name.clone(),
+        level: player.level(),
+        exp: player.exp,
+        next_exp: next_level_exp(player),
+        password,
+    })
+}
+
+/// のろいを解き、のろわれたどうぐを取り除く
+pub fn remove_curse(player: &mut Player) -> Result<CurseRemoval, ServiceError> {
+    if !player.is_cursed() {
+        return Err(ServiceError::NotCursed);
+    }
+    let mut removed = Vec::new();
+    for item in player.cursed_items() {
+        if player.remove_item(item.id()) {
+            removed.push(item.name().to_string());
+        }
+    }
+    player.lift_curse();
+    Ok(CurseRemoval { removed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::item_weapon::{CURSED_ITEM_IDS, ITEM_MASTER};
+    use crate::constants::town::TOWN_MASTER;
+    use crate::player::PlayerArgs;
+
+    fn player_with_gold(gold: u16) -> Player {
+        Player::new_with(PlayerArgs {
+            name: Some("ゆうてい".to_string()),
+            level: Some(5),
+            gold: Some(gold),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_stay_at_inn() {
+        let town = TOWN_MASTER.iter().find(|t| t.inn_price.is_some()).unwrap();
+        let price = town.inn_price.unwrap();
+        let mut player = player_with_gold(price);
+        player.hp = 1;
+        player.mp = 0;
+        let stay = stay_at_inn(&mut player, town).unwrap();
+        assert_eq!(stay.gold, 0);
+        assert_eq!(player.hp, player.max_hp());
+        assert_eq!(player.mp, player.max_mp());
+
+        player.hp = 1;
+        assert_eq!(
+            stay_at_inn(&mut player, town),
+            Err(ServiceError::NotEnoughGold { price, gold: 0 })
+        );
+        assert_eq!(player.hp, 1);
+
+        let no_inn = TOWN_MASTER.iter().find(|t| t.inn_price.is_none()).unwrap();
+        assert_eq!(stay_at_inn(&mut player, no_inn), Err(ServiceError::NoInn));
+    }
+
+    #[test]
+    fn test_talk_to_king() {
+        let player = player_with_gold(0);
+        let save = talk_to_king(&player).unwrap();
+        let required = get_status_by_level(6).unwrap().required_exp;
+        assert_eq!(save.level, 5);
+        assert_eq!(save.next_exp, Some(required - player.exp));
+        assert_eq!(save.password, player.to_password_string().unwrap());
+        assert!(save.message_lines().contains(&save.password));
+
+        let max = Player::new_with(PlayerArgs {
+            level: Some(30),
+            ..Default::default()
+        });
+        assert_eq!(next_level_exp(&max), None);
+    }
+
+    #[test]
+    fn test_remove_curse() {
+        let mut player = player_with_gold(0);
+        assert_eq!(remove_curse(&mut player), Err(ServiceError::NotCursed));
+
+        player.items[0] = CURSED_ITEM_IDS[0];
+        player.items[1] = 1;
+        player.is_curse_belt = true;
+        let removal = remove_curse(&mut player).unwrap();
+        assert_eq!(removal.removed, vec![ITEM_MASTER[9].name.to_string()]);
+        assert!(!player.is_curse_belt);
+        assert_eq!(player.items[0], 1);
+    }
+}
@@ -1,6 +1,9 @@
+use crate::battle::BattleSnapshot;
 use crate::constants::battle::PlayerAction;
 
 pub trait PlayerInput {
     fn get_player_input(&mut self, max: usize) -> usize;
     fn get_player_action(&mut self, display_commands: &mut dyn FnMut()) -> PlayerAction;
+    /// コマンド入力の直前に呼ばれる。自動戦闘など状況を見て判断する入力で使う
+    fn observe_battle(&mut self, _snapshot: &BattleSnapshot) {}
 }
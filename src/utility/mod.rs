@@ -4,3 +4,4 @@ pub mod random_utils;
 pub mod spell_utils;
 pub mod status_utils;
 pub mod string_utils;
+pub mod town_utils;
@@ -7,10 +7,14 @@ pub fn random_value(n: u8) -> u8 {
 }
 
 pub fn generate_in_range(min: u8, max: u8) -> u8 {
+    generate_in_range_with_rand(min, max, random_value(BIT_8_MAX))
+}
+
+pub fn generate_in_range_with_rand(min: u8, max: u8, rand_val: u8) -> u8 {
     if min >= max {
         return min;
     }
-    let rand = random_value(BIT_8_MAX) as u16;
+    let rand = rand_val as u16;
     let min = min as u16;
     let max = max as u16;
     let range = max - min;
@@ -39,6 +43,26 @@ pub fn random_success_by_percent(percent: f64) -> bool {
     rand_val < threshold
 }
 
+/// random_success_by_ratio が true を返す確率
+pub fn success_rate_by_ratio(denominator: u16) -> f64 {
+    if denominator == 0 {
+        return 1.0;
+    }
+    (256 / denominator).min(256) as f64 / 256.0
+}
+
+/// random_success_by_percent が true を返す確率
+pub fn success_rate_by_percent(percent: f64) -> f64 {
+    if percent <= 0.0 {
+        return 0.0;
+    }
+    if percent >= 100.0 {
+        return 1.0;
+    }
+    let threshold = (percent * 256.0 / 100.0).round();
+    threshold.min(256.0) / 256.0
+}
+
 pub fn get_escape_rand_max_by_monster_index(index: usize) -> u8 {
     match index {
         0..=19 => 63,
@@ -89,4 +113,20 @@ mod tests {
             success_rate * 100.0
         );
     }
+
+    #[test]
+    fn test_success_rates_match_thresholds() {
+        assert_eq!(success_rate_by_ratio(32), 8.0 / 256.0);
+        assert_eq!(success_rate_by_percent(50.0), 0.5);
+        assert_eq!(success_rate_by_percent(1.6), 4.0 / 256.0);
+        assert_eq!(success_rate_by_percent(0.0), 0.0);
+        assert_eq!(success_rate_by_percent(100.0), 1.0);
+    }
+
+    #[test]
+    fn test_generate_in_range_with_rand_bounds() {
+        assert_eq!(generate_in_range_with_rand(10, 17, 0), 10);
+        assert_eq!(generate_in_range_with_rand(10, 17, 255), 16);
+        assert_eq!(generate_in_range_with_rand(5, 5, 128), 5);
+    }
 }
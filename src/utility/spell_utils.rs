@@ -1,6 +1,7 @@
+use crate::constants::config::BIT_8_MAX;
 use crate::constants::monster::ActionType;
 use crate::constants::spell::{SPELL_INFO_LIST, Spell, SpellInfo};
-use crate::utility::random_utils::generate_in_range;
+use crate::utility::random_utils::{generate_in_range_with_rand, random_value};
 use std::str::FromStr;
 
 pub fn spells_learned_by_level(level: u8) -> Vec<&'static SpellInfo> {
@@ -23,27 +24,35 @@ pub fn get_spell_info_by_name(name: &str) -> Option<&'static SpellInfo> {
 }
 
 pub fn player_spell_effect(spell: Spell) -> u8 {
+    player_spell_effect_with_rand(spell, random_value(BIT_8_MAX))
+}
+
+pub fn player_spell_effect_with_rand(spell: Spell, rand_val: u8) -> u8 {
     match spell {
-        Spell::Hoimi => generate_in_range(10, 17),
-        Spell::Gira => generate_in_range(5, 12),
-        Spell::Behoimi => generate_in_range(85, 100),
-        Spell::Begirama => generate_in_range(58, 65),
+        Spell::Hoimi => generate_in_range_with_rand(10, 17, rand_val),
+        Spell::Gira => generate_in_range_with_rand(5, 12, rand_val),
+        Spell::Behoimi => generate_in_range_with_rand(85, 100, rand_val),
+        Spell::Begirama => generate_in_range_with_rand(58, 65, rand_val),
         _ => 0,
     }
 }
 
 pub fn monster_action_effect(action: &ActionType) -> u8 {
+    monster_action_effect_with_rand(action, random_value(BIT_8_MAX))
+}
+
+pub fn monster_action_effect_with_rand(action: &ActionType, rand_val: u8) -> u8 {
     match action {
         ActionType::Spell(spell) => match spell {
-            Spell::Hoimi => generate_in_range(20, 27),
-            Spell::Behoimi => generate_in_range(85, 100),
-            Spell::Gira => generate_in_range(3, 10),
-            Spell::Begirama => generate_in_range(30, 45),
+            Spell::Hoimi => generate_in_range_with_rand(20, 27, rand_val),
+            Spell::Behoimi => generate_in_range_with_rand(85, 100, rand_val),
+            Spell::Gira => generate_in_range_with_rand(3, 10, rand_val),
+            Spell::Begirama => generate_in_range_with_rand(30, 45, rand_val),
             _ => 0,
         },
         ActionType::Special(name) => match *name {
-            "ほのお(弱)" => generate_in_range(16, 23),
-            "ほのお(強)" => generate_in_range(65, 72),
+            "ほのお(弱)" => generate_in_range_with_rand(16, 23, rand_val),
+            "ほのお(強)" => generate_in_range_with_rand(65, 72, rand_val),
             _ => 0,
         },
     }
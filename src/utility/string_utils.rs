@@ -37,6 +37,33 @@ pub fn split_dakuten(input: &str) -> String {
     result
 }
 
+/// 濁音結合（split_dakuten の逆）
+pub fn join_dakuten(input: &str) -> String {
+    let mut result: Vec<char> = Vec::new();
+    for c in input.chars() {
+        let pairs = match c {
+            '゛' => DAKUTEN_PAIRS,
+            '゜' => HANDAKUTEN_PAIRS,
+            _ => {
+                result.push(c);
+                continue;
+            }
+        };
+        let voiced = result
+            .last()
+            .and_then(|last| pairs.iter().find(|(_, base)| base == last))
+            .map(|(voiced, _)| *voiced);
+        match voiced {
+            Some(voiced) => {
+                result.pop();
+                result.push(voiced);
+            }
+            None => result.push(c),
+        }
+    }
+    result.into_iter().collect()
+}
+
 /// 4文字整形
 pub fn normalize_to_4_chars(input: &str) -> String {
     let mut chars: Vec<char> = input.chars().collect();
@@ -99,6 +126,16 @@ mod tests {
         assert_eq!(split_dakuten("ばななとぱいん"), "は゛ななとは゜いん");
     }
 
+    #[test]
+    fn test_join_dakuten() {
+        assert_eq!(join_dakuten("た゛いか゛く"), "だいがく");
+        assert_eq!(
+            join_dakuten(&split_dakuten("ばななとぱいん")),
+            "ばななとぱいん"
+        );
+        assert_eq!(join_dakuten("゛あ゛"), "゛あ゛"); // 濁点の付かない文字はそのまま
+    }
+
     #[test]
     fn test_split_dakuten_empty() {
         assert_eq!(split_dakuten(""), "");
@@ -0,0 +1,143 @@
+use crate::constants::item_weapon::{
+    ARMOR_MASTER, Equipment, FAIRY_WATER_ID, ITEM_MASTER, SHIELD_MASTER, WEAPON_MASTER,
+};
+use crate::constants::town::{HERB_PRICE, TOWN_MASTER, Town};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ShopCategory {
+    Weapon,
+    Armor,
+    Shield,
+    Item,
+    Herb,
+    Key,
+}
+
+/// 店に並ぶ商品（id はカテゴリごとのマスターの ID。やくそう・かぎ は 0）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShopEntry {
+    pub category: ShopCategory,
+    pub id: u8,
+    pub name: String,
+    pub price: u16,
+}
+
+pub fn get_town_by_name(name: &str) -> Option<(usize, &'static Town)> {
+    TOWN_MASTER
+        .iter()
+        .enumerate()
+        .find(|(_, town)| town.name == name)
+}
+
+pub fn list_town_names() -> Vec<&'static str> {
+    TOWN_MASTER.iter().map(|t| t.name).collect()
+}
+
+fn entries_from(
+    category: ShopCategory,
+    ids: &[u8],
+    master: &'static [Equipment],
+) -> impl Iterator<Item = ShopEntry> {
+    ids.iter().map(move |&id| {
+        let equipment = &master[id as usize];
+        ShopEntry {
+            category,
+            id,
+            name: equipment.name.to_string(),
+            price: equipment.price,
+        }
+    })
+}
+
+/// ぶきや → どうぐや → かぎ の順の品揃え
+pub fn shop_entries(town: &Town) -> Vec<ShopEntry> {
+    let mut entries: Vec<ShopEntry> =
+        entries_from(ShopCategory::Weapon, town.weapons, &WEAPON_MASTER)
+            .chain(entries_from(
+                ShopCategory::Armor,
+                town.armors,
+                &ARMOR_MASTER,
+            ))
+            .chain(entries_from(
+                ShopCategory::Shield,
+                town.shields,
+                &SHIELD_MASTER,
+            ))
+            .collect();
+    if town.sells_herbs {
+        entries.push(ShopEntry {
+            category: ShopCategory::Herb,
+            id: 0,
+            name: "やくそう".to_string(),
+            price: HERB_PRICE,
+        });
+    }
+    entries.extend(entries_from(ShopCategory::Item, town.items, &ITEM_MASTER));
+    if town.fairy_water {
+        entries.extend(entries_from(
+            ShopCategory::Item,
+            &[FAIRY_WATER_ID],
+            &ITEM_MASTER,
+        ));
+    }
+    if let Some(price) = town.key_price {
+        entries.push(ShopEntry {
+            category: ShopCategory::Key,
+            id: 0,
+            name: "まほうのかぎ".to_string(),
+            price,
+        });
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::world::WORLD_ENTRANCES;
+
+    #[test]
+    fn test_shop_ids_are_purchasable_master_entries() {
+        for town in TOWN_MASTER.iter() {
+            for entry in shop_entries(town) {
+                assert!(entry.price > 0, "{}: {}", town.name, entry.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_town_has_a_world_entrance() {
+        for town in TOWN_MASTER.iter() {
+            assert!(
+                WORLD_ENTRANCES.iter().any(|e| e.name == town.name),
+                "{}",
+                town.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_shop_entries_of_brecconary() {
+        let (index, town) = get_town_by_name("ラダトームのまち").unwrap();
+        assert_eq!(index, 1);
+        let names: Vec<String> = shop_entries(town).into_iter().map(|e| e.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "たけざお",
+                "こんぼう",
+                "どうのつるぎ",
+                "ぬののふく",
+                "かわのふく",
+                "かわのたて",
+                "やくそう",
+                "たいまつ",
+                "りゅうのうろこ",
+                "せいすい"
+            ]
+        );
+        assert!(!TOWN_MASTER[5].has_shop());
+        assert!(get_town_by_name("ロンダルキア").is_none());
+    }
+}
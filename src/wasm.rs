@@ -12,7 +12,26 @@ use crate::constants::battle::PlayerAction;
 use crate::constants::monster::MONSTER_MASTER;
 use crate::constants::item_weapon::{ITEM_MASTER, WEAPON_MASTER, ARMOR_MASTER, SHIELD_MASTER};
 use crate::constants::spell::SPELL_INFO_LIST;
+use crate::constants::town::TOWN_MASTER;
+use crate::constants::quest::Milestone;
+use crate::constants::world::{Terrain, RAINBOW_BRIDGE_POSITION};
+use crate::utility::town_utils::{shop_entries, ShopEntry};
+use crate::shop::{buy_from_town, compare_entry, sell};
+use crate::growth_type::GrowthReport;
+use crate::name_optimizer::{optimize_name, NameGoal, NameSearch, DEFAULT_NAME_LIMIT};
+use crate::equipment::{best_affordable, compare_equip, equip, unequip, EquipSlot};
+use crate::town_service::{remove_curse, stay_at_inn, talk_to_king};
+use crate::death::{apply_death_penalty, DeathOptions};
+use crate::inventory::{add_item, drop_item, menu_lines, sort_items};
+use crate::exploration::{Exploration, ExploreCommand, ExploreEvent};
+use crate::quest::QuestProgress;
+use crate::world_state::WorldState;
+use crate::route::{find_route, parse_location, Route, RouteOptions};
+use crate::world::World;
+use crate::constants::message::NPC_MASTER;
+use crate::dialogue::lookup_message;
 use crate::battle::Battle;
+use crate::calculator::analyze_matchup;
 use crate::monster::Monster;
 
 /// Main WASM game interface
@@ -21,6 +40,9 @@ pub struct WasmGame {
     player: Option<Player>,
     output_buffer: BufferOutput,
     web_input: WebInput,
+    exploration: Option<Exploration>,
+    world_state: WorldState,
+    death_options: Option<DeathOptions>,
 }
 
 /// Player state for JavaScript
@@ -87,6 +109,67 @@ pub struct StatusData {
     pub spell: Option<String>,
 }
 
+/// Town data for JavaScript
+#[derive(Serialize, Deserialize)]
+pub struct TownData {
+    pub id: usize,
+    pub name: String,
+    pub shop: Vec<ShopEntry>,
+    pub inn_price: Option<u16>,
+    pub key_price: Option<u16>,
+    pub fairy_water: bool,
+}
+
+/// Town service result with its message lines for JavaScript
+#[derive(Serialize, Deserialize)]
+pub struct ServiceData<T> {
+    pub result: T,
+    pub messages: Vec<String>,
+}
+
+/// Quest progress for JavaScript
+#[derive(Serialize, Deserialize)]
+pub struct QuestData {
+    pub percent: u8,
+    pub completed: Vec<String>,
+    pub next_steps: Vec<String>,
+    pub missing_for_ending: Vec<String>,
+}
+
+/// Exploration step result for JavaScript
+#[derive(Serialize, Deserialize)]
+pub struct ExploreResult {
+    pub events: Vec<ExploreEvent>,
+    pub messages: Vec<String>,
+    pub x: usize,
+    pub y: usize,
+    pub facing: String,
+    pub dungeon: Option<String>,
+    pub floor: Option<u8>,
+    pub view: Vec<String>,
+    pub step_damage: u32,
+    pub step_healed: u32,
+    pub final_player_state: PlayerState,
+}
+
+/// Town people for JavaScript
+#[derive(Serialize, Deserialize)]
+pub struct NpcData {
+    pub id: usize,
+    pub town: String,
+    pub name: String,
+    pub x: u8,
+    pub y: u8,
+}
+
+/// Route plan for JavaScript
+#[derive(Serialize, Deserialize)]
+pub struct RouteData {
+    pub route: Route,
+    pub moves: String,
+    pub overlay: Vec<String>,
+}
+
 #[wasm_bindgen]
 impl WasmGame {
     /// Create a new WasmGame instance
@@ -100,6 +183,9 @@ impl WasmGame {
             player: None,
             output_buffer: BufferOutput::new(),
             web_input: WebInput::new(),
+            exploration: None,
+            world_state: WorldState::new(),
+            death_options: None,
         }
     }
 
@@ -114,6 +200,8 @@ impl WasmGame {
             items: player.item_list().iter().map(|s| s.to_string()).collect(),
         };
 
+        // 探索中の古いプレイヤーに戻らないよう 探索は捨てる
+        self.exploration = None;
         self.player = Some(player);
 
         serde_wasm_bindgen::to_value(&state)
@@ -122,13 +210,9 @@ impl WasmGame {
 
     /// Generate a password from the current player state
     pub fn generate_password(&self) -> Result<String, JsValue> {
-        match &self.player {
-            Some(player) => {
-                player.to_password_string()
-                    .map_err(|e| JsValue::from_str(&e))
-            }
-            None => Err(JsValue::from_str("プレイヤーが作成されていません"))
-        }
+        self.current_player()?
+            .to_password_string()
+            .map_err(|e| JsValue::from_str(&e))
     }
 
     /// Load player from a password string
@@ -142,7 +226,12 @@ impl WasmGame {
             items: player.item_list().iter().map(|s| s.to_string()).collect(),
         };
 
+        // 探索中なら 探索ごと捨ててから 読み込んだプレイヤーに入れかえる
+        // （そのままだと end_exploration で 探索中の古いプレイヤーに戻ってしまう）
+        self.exploration = None;
         self.player = Some(player);
+        // ふっかつのじゅもん を入れ直すと たからばこ・とびら は元に戻る
+        self.world_state.reset();
 
         serde_wasm_bindgen::to_value(&state)
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
@@ -150,24 +239,20 @@ impl WasmGame {
 
     /// Get current player state
     pub fn get_player_state(&self) -> Result<JsValue, JsValue> {
-        match &self.player {
-            Some(player) => {
-                let state = PlayerState {
-                    summary: player.summary(),
-                    strength_status: player.strength_status(),
-                    items: player.item_list().iter().map(|s| s.to_string()).collect(),
-                };
-
-                serde_wasm_bindgen::to_value(&state)
-                    .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
-            }
-            None => Err(JsValue::from_str("プレイヤーが作成されていません"))
-        }
+        let player = self.current_player()?;
+        let state = PlayerState {
+            summary: player.summary(),
+            strength_status: player.strength_status(),
+            items: player.item_list().iter().map(|s| s.to_string()).collect(),
+        };
+
+        serde_wasm_bindgen::to_value(&state)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
 
     /// Check if player exists
     pub fn has_player(&self) -> bool {
-        self.player.is_some()
+        self.player.is_some() || self.exploration.is_some()
     }
 
     /// Get accumulated messages
@@ -203,6 +288,9 @@ impl WasmGame {
     /// Actions must be queued beforehand using queue_battle_action and queue_battle_input
     /// Returns BattleResult as JsValue
     pub fn run_battle(&mut self, monster_id: usize) -> Result<JsValue, JsValue> {
+        if self.exploration.is_some() {
+            return Err(JsValue::from_str("たんさくちゅうは explore の せんとうを つかってください"));
+        }
         let player = self.player.take()
             .ok_or_else(|| JsValue::from_str("プレイヤーが作成されていません"))?;
 
@@ -257,6 +345,17 @@ impl WasmGame {
         self.web_input.clear();
     }
 
+    /// Calculate damage ranges and probabilities between the current player and a monster
+    /// Returns MatchupReport as JsValue
+    pub fn calculate_damage(&self, monster_id: usize) -> Result<JsValue, JsValue> {
+        let player = self.current_player()?;
+
+        let report = analyze_matchup(player, &Monster::new(monster_id));
+
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
     /// Get all monster data
     pub fn get_monsters(&self) -> Result<JsValue, JsValue> {
         let monsters: Vec<MonsterData> = MONSTER_MASTER
@@ -389,9 +488,400 @@ impl WasmGame {
         serde_wasm_bindgen::to_value(&statuses)
             .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
     }
+
+    /// Buy the entry at `index` (0-based) of a town's shop
+    /// Returns Purchase on success, or a ShopError object on failure
+    pub fn shop_buy(&mut self, town_id: usize, index: usize) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+        let town = TOWN_MASTER.get(town_id)
+            .ok_or_else(|| JsValue::from_str("町が見つかりません"))?;
+
+        let purchase = buy_from_town(player, town, index)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        serde_wasm_bindgen::to_value(&purchase)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Sell an item (101: herb, 102: key, otherwise ITEM_MASTER id)
+    /// Returns Sale on success, or a ShopError object on failure
+    pub fn shop_sell(&mut self, item_id: u8) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+
+        let sale = sell(player, item_id)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        serde_wasm_bindgen::to_value(&sale)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Stay at a town's inn and restore HP/MP
+    /// Returns InnStay with messages, or a ServiceError object on failure
+    pub fn stay_at_inn(&mut self, town_id: usize) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+        let town = TOWN_MASTER.get(town_id)
+            .ok_or_else(|| JsValue::from_str("町が見つかりません"))?;
+
+        let stay = stay_at_inn(player, town)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        let messages = stay.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: stay, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Talk to the king to get the password and the experience needed for the next level
+    pub fn talk_to_king(&self) -> Result<JsValue, JsValue> {
+        let player = self.current_player()?;
+
+        let save = talk_to_king(player)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        let messages = save.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: save, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Remove the curse and the cursed items
+    /// Returns CurseRemoval with messages, or a ServiceError object on failure
+    pub fn remove_curse(&mut self) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+
+        let removal = remove_curse(player)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        let messages = removal.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: removal, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Revive a dead player at the castle: gold is halved and HP/MP are fully restored
+    /// The curse stays only when keep_curse was set with set_death_penalty
+    pub fn revive(&mut self) -> Result<JsValue, JsValue> {
+        let options = self.death_options.clone().unwrap_or_default();
+        let player = self.current_player_mut()?;
+        if player.is_alive() {
+            return Err(JsValue::from_str("プレイヤーは いきています"));
+        }
+
+        let revival = apply_death_penalty(player, &options);
+        let messages = revival.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: revival, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Apply the death penalty when the player dies while exploring
+    /// When disabled, exploration stops at the player's death
+    pub fn set_death_penalty(&mut self, enabled: bool, keep_curse: bool) {
+        self.death_options = enabled.then_some(DeathOptions { keep_curse });
+        if let Some(exploration) = self.exploration.as_mut() {
+            exploration.revive = self.death_options.clone();
+        }
+    }
+
+    /// Add an item (101: herb, 102: key, otherwise ITEM_MASTER id) to the first free slot
+    /// Throws an InventoryError object when the slots or the herb/key limit are full
+    pub fn add_item(&mut self, item_id: u8) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+
+        add_item(player, item_id)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        serde_wasm_bindgen::to_value(&player.unified_item_list())
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Drop an item. Quest items and stuck cursed items cannot be dropped
+    pub fn drop_item(&mut self, item_id: u8) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+
+        drop_item(player, item_id)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        serde_wasm_bindgen::to_value(&player.unified_item_list())
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Sort the item slots in the original's menu order
+    pub fn sort_items(&mut self) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+
+        sort_items(player);
+        serde_wasm_bindgen::to_value(&player.unified_item_list())
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Get the item menu lines as the battle menu shows them
+    pub fn get_item_menu(&self) -> Result<Vec<String>, JsValue> {
+        let player = self.current_player()?;
+        Ok(menu_lines(&player.unified_item_list()))
+    }
+
+    /// Compare the current equipment with the given one without changing the player
+    /// slot: "weapon" | "armor" | "shield", id: index of WEAPON/ARMOR/SHIELD_MASTER
+    pub fn compare_equipment(&self, slot: &str, id: u8) -> Result<JsValue, JsValue> {
+        let player = self.current_player()?;
+        let slot = slot.parse::<EquipSlot>().map_err(|e| JsValue::from_str(&e))?;
+
+        let comparison = compare_equip(player, slot, id)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        let messages = comparison.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: comparison, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Equip without buying. Returns the before/after comparison, or an EquipError object
+    pub fn equip(&mut self, slot: &str, id: u8) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+        let slot = slot.parse::<EquipSlot>().map_err(|e| JsValue::from_str(&e))?;
+
+        let comparison = equip(player, slot, id)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        let messages = comparison.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: comparison, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Take off the equipment in the slot
+    pub fn unequip(&mut self, slot: &str) -> Result<JsValue, JsValue> {
+        let player = self.current_player_mut()?;
+        let slot = slot.parse::<EquipSlot>().map_err(|e| JsValue::from_str(&e))?;
+
+        let comparison = unequip(player, slot)
+            .map_err(|e| serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| JsValue::from_str(&e.to_string())))?;
+        let messages = comparison.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: comparison, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Find the strongest shop equipment the budget can buy (old equipment is traded in)
+    pub fn best_equipment(&self, budget: u16) -> Result<JsValue, JsValue> {
+        let player = self.current_player()?;
+
+        let best = best_affordable(player, budget);
+        let messages = best.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: best, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Get the comparison for each shop entry of a town (null for herbs, keys and items)
+    /// The order matches the shop list returned by get_towns
+    pub fn get_shop_comparisons(&self, town_id: usize) -> Result<JsValue, JsValue> {
+        let player = self.current_player()?;
+        let town = TOWN_MASTER.get(town_id)
+            .ok_or_else(|| JsValue::from_str("町が見つかりません"))?;
+
+        let comparisons: Vec<_> = shop_entries(town)
+            .iter()
+            .map(|entry| compare_entry(player, entry))
+            .collect();
+        serde_wasm_bindgen::to_value(&comparisons)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Find the best growth type for a goal ("strength", "mp" or "balanced") and names of that type
+    /// prefix: names must start with it, words: pick only from this list, near: sort by closeness to this name
+    pub fn optimize_name(&self, goal: &str, prefix: Option<String>, words: Vec<String>, near: Option<String>, limit: Option<usize>) -> Result<JsValue, JsValue> {
+        let goal = goal.parse::<NameGoal>().map_err(|e| JsValue::from_str(&e))?;
+        let search = NameSearch {
+            prefix: prefix.filter(|p| !p.is_empty()),
+            words,
+            near: near.filter(|n| !n.is_empty()),
+            limit: limit.unwrap_or(DEFAULT_NAME_LIMIT),
+        };
+
+        let report = optimize_name(goal, &search).map_err(|e| JsValue::from_str(&e))?;
+        let messages = report.message_lines();
+        serde_wasm_bindgen::to_value(&ServiceData { result: report, messages })
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Get the growth table (stats and spell per level) of a name, or of all 16 growth types when name is omitted
+    pub fn get_growth_table(&self, name: Option<String>) -> Result<JsValue, JsValue> {
+        let report = match name.filter(|n| !n.is_empty()) {
+            Some(name) => GrowthReport::by_name(&name),
+            None => GrowthReport::all(),
+        };
+        serde_wasm_bindgen::to_value(&report)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Same as get_growth_table, as CSV text
+    pub fn get_growth_csv(&self, name: Option<String>) -> String {
+        match name.filter(|n| !n.is_empty()) {
+            Some(name) => GrowthReport::by_name(&name).to_csv(),
+            None => GrowthReport::all().to_csv(),
+        }
+    }
+
+    /// Get all town data (shops, inn and key prices)
+    pub fn get_towns(&self) -> Result<JsValue, JsValue> {
+        let towns: Vec<TownData> = TOWN_MASTER
+            .iter()
+            .enumerate()
+            .map(|(id, town)| TownData {
+                id,
+                name: town.name.to_string(),
+                shop: shop_entries(town),
+                inn_price: town.inn_price,
+                key_price: town.key_price,
+                fairy_water: town.fairy_water,
+            })
+            .collect();
+
+        serde_wasm_bindgen::to_value(&towns)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    /// Run one exploration command (n/s/e/w, search, talk, stairs, door)
+    /// The first call moves the player into the field at Tantegel
+    /// Battles consume actions queued with queue_battle_action
+    /// Returns ExploreResult as JsValue
+    pub fn explore(&mut self, command: &str) -> Result<JsValue, JsValue> {
+        let command = command.parse::<ExploreCommand>()
+            .map_err(|e| JsValue::from_str(&e))?;
+        if self.exploration.is_none() {
+            let player = self.player.take()
+                .ok_or_else(|| JsValue::from_str("プレイヤーが作成されていません"))?;
+            let mut exploration = Exploration::new(player);
+            exploration.world_state = std::mem::take(&mut self.world_state);
+            exploration.revive = self.death_options.clone();
+            self.exploration = Some(exploration);
+        }
+        let exploration = self.exploration.as_mut().unwrap();
+
+        self.output_buffer.buffer.clear();
+        let events = exploration.execute(command, &mut self.web_input, &mut self.output_buffer);
+        let mut messages = self.output_buffer.buffer.clone();
+        messages.extend(events.iter().map(|e| e.message()));
+
+        let player = &exploration.player;
+        let result = ExploreResult {
+            events,
+            messages,
+            x: exploration.x,
+            y: exploration.y,
+            facing: exploration.facing.as_str().to_string(),
+            dungeon: exploration.dungeon.as_ref().map(|d| d.name.clone()),
+            floor: exploration.dungeon.as_ref().map(|d| d.floor),
+            view: exploration.render_view().unwrap_or_default(),
+            step_damage: exploration.step_damage,
+            step_healed: exploration.step_healed,
+            final_player_state: PlayerState {
+                summary: player.summary(),
+                strength_status: player.strength_status(),
+                items: player.item_list().iter().map(|s| s.to_string()).collect(),
+            },
+        };
+
+        serde_wasm_bindgen::to_value(&result)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Leave the field and make the player available to the other APIs again
+    pub fn end_exploration(&mut self) {
+        if let Some(mut exploration) = self.exploration.take() {
+            self.world_state = std::mem::take(&mut exploration.world_state);
+            self.player = Some(exploration.into_player());
+        }
+    }
+
+    /// Check if the player is exploring the field
+    pub fn is_exploring(&self) -> bool {
+        self.exploration.is_some()
+    }
+
+    /// Get story progress of the current player (or the exploring player)
+    /// Returns QuestData as JsValue
+    pub fn get_quest_progress(&self) -> Result<JsValue, JsValue> {
+        let progress = match (&self.player, &self.exploration) {
+            (Some(player), _) => QuestProgress::new(player, false),
+            (None, Some(exploration)) => {
+                let (bx, by) = RAINBOW_BRIDGE_POSITION;
+                let bridge_built = exploration.world.tile_at(bx, by) == Some(Terrain::Bridge);
+                QuestProgress::new(&exploration.player, bridge_built)
+            }
+            (None, None) => return Err(JsValue::from_str("プレイヤーが作成されていません")),
+        };
+        let names = |milestones: Vec<Milestone>| {
+            milestones.iter().map(|m| m.as_str().to_string()).collect()
+        };
+        let data = QuestData {
+            percent: progress.percent(),
+            completed: names(progress.completed()),
+            next_steps: names(progress.next_steps()),
+            missing_for_ending: names(progress.missing_for_ending()),
+        };
+
+        serde_wasm_bindgen::to_value(&data)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Get all town people in the message catalog
+    /// Returns Vec<NpcData> as JsValue
+    pub fn get_npcs(&self) -> Result<JsValue, JsValue> {
+        let npcs: Vec<NpcData> = NPC_MASTER
+            .iter()
+            .enumerate()
+            .map(|(id, npc)| NpcData {
+                id,
+                town: npc.town.to_string(),
+                name: npc.name.to_string(),
+                x: npc.x,
+                y: npc.y,
+            })
+            .collect();
+
+        serde_wasm_bindgen::to_value(&npcs)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
+
+    /// Get what a town person says to the current player (or the exploring player)
+    pub fn get_npc_message(&self, town: &str, name: &str) -> Result<String, JsValue> {
+        let player = self.current_player()?;
+        lookup_message(town, name, player).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Plan a route on the world map between two entrance names or "x,y"
+    /// options: "avoid-damage", "avoid-danger"
+    /// Returns RouteData as JsValue
+    pub fn plan_route(&self, from: &str, to: &str, options: Vec<String>) -> Result<JsValue, JsValue> {
+        let (player, world) = match (&self.player, &self.exploration) {
+            (Some(player), _) => (player, World::new()),
+            (None, Some(exploration)) => (&exploration.player, exploration.world.clone()),
+            (None, None) => return Err(JsValue::from_str("プレイヤーが作成されていません")),
+        };
+        let start = parse_location(&world, from).map_err(|e| JsValue::from_str(&e))?;
+        let goal = parse_location(&world, to).map_err(|e| JsValue::from_str(&e))?;
+        let options = RouteOptions::from_words(&options).map_err(|e| JsValue::from_str(&e))?;
+        let route = find_route(&world, player, start, goal, &options)
+            .map_err(|e| JsValue::from_str(&e))?;
+
+        let data = RouteData {
+            moves: route.moves_string(),
+            overlay: route.render_overlay(&world, 2),
+            route,
+        };
+
+        serde_wasm_bindgen::to_value(&data)
+            .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+    }
 }
 
 // Default implementation
+impl WasmGame {
+    /// 探索中は 探索しているプレイヤーを読み書きする
+    fn current_player(&self) -> Result<&Player, JsValue> {
+        self.exploration.as_ref().map(|exploration| &exploration.player)
+            .or(self.player.as_ref())
+            .ok_or_else(|| JsValue::from_str("プレイヤーが作成されていません"))
+    }
+
+    fn current_player_mut(&mut self) -> Result<&mut Player, JsValue> {
+        match self.exploration.as_mut() {
+            Some(exploration) => Ok(&mut exploration.player),
+            None => self.player.as_mut()
+                .ok_or_else(|| JsValue::from_str("プレイヤーが作成されていません")),
+        }
+    }
+}
+
 impl Default for WasmGame {
     fn default() -> Self {
         Self::new()
@@ -464,6 +954,174 @@ mod tests {
         game.clear_battle_input();
     }
 
+    #[wasm_bindgen_test]
+    fn test_calculate_damage() {
+        let mut game = WasmGame::new();
+        assert!(game.calculate_damage(0).is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        assert!(game.calculate_damage(0).is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_shop_buy_and_sell() {
+        let mut game = WasmGame::new();
+        assert!(game.shop_buy(1, 0).is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        // 所持金 0 では買えない
+        assert!(game.shop_buy(1, 0).is_err());
+        assert!(game.shop_sell(1).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_town_services() {
+        let mut game = WasmGame::new();
+        assert!(game.talk_to_king().is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        assert!(game.talk_to_king().is_ok());
+        // 所持金 0 では泊まれない
+        assert!(game.stay_at_inn(1).is_err());
+        assert!(game.remove_curse().is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_revive() {
+        let mut game = WasmGame::new();
+        assert!(game.revive().is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        // いきている ときは 生き返れない
+        assert!(game.revive().is_err());
+        game.set_death_penalty(true, false);
+        game.player.as_mut().unwrap().hp = 0;
+        assert!(game.revive().is_ok());
+        assert!(game.player.as_ref().unwrap().is_alive());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_inventory() {
+        let mut game = WasmGame::new();
+        assert!(game.add_item(1).is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        assert!(game.add_item(101).is_ok());
+        assert!(game.add_item(7).is_ok());
+        // ロトのしるし は すてられない
+        assert!(game.drop_item(7).is_err());
+        assert!(game.drop_item(101).is_ok());
+        assert!(game.sort_items().is_ok());
+        assert_eq!(game.get_item_menu().unwrap(), vec!["1: ロトのしるし"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_equipment() {
+        let mut game = WasmGame::new();
+        assert!(game.equip("weapon", 1).is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        assert!(game.compare_equipment("weapon", 3).is_ok());
+        assert!(game.compare_equipment("weapon", 99).is_err());
+        assert!(game.compare_equipment("ring", 1).is_err());
+        assert!(game.equip("weapon", 3).is_ok());
+        assert_eq!(game.player.as_ref().unwrap().weapon, 3);
+        assert!(game.equip("weapon", 3).is_err());
+        assert!(game.unequip("weapon").is_ok());
+        assert!(game.unequip("weapon").is_err());
+        assert!(game.best_equipment(500).is_ok());
+        assert!(game.get_shop_comparisons(1).is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_optimize_name() {
+        let game = WasmGame::new();
+        assert!(game.optimize_name("strength", Some("ゆう".to_string()), Vec::new(), None, Some(3)).is_ok());
+        assert!(game.optimize_name("mp", None, vec!["ゆうてい".to_string()], Some("ゆうてい".to_string()), None).is_ok());
+        assert!(game.optimize_name("luck", None, Vec::new(), None, None).is_err());
+        assert!(game.optimize_name("balanced", Some("X".to_string()), Vec::new(), None, None).is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_growth_table() {
+        let game = WasmGame::new();
+        assert!(game.get_growth_table(Some("ゆうてい".to_string())).is_ok());
+        assert!(game.get_growth_table(None).is_ok());
+        assert_eq!(game.get_growth_csv(None).lines().count(), 1 + 16 * 30);
+        assert!(game.get_growth_csv(Some("だい".to_string())).contains("\n6,だい,1,"));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_get_towns() {
+        let game = WasmGame::new();
+        assert!(game.get_towns().is_ok());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_explore() {
+        let mut game = WasmGame::new();
+        assert!(game.explore("n").is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        assert!(game.explore("jump").is_err());
+        assert!(game.explore("search").is_ok());
+        assert!(game.is_exploring());
+        assert!(game.has_player());
+
+        assert!(game.get_quest_progress().is_ok());
+        // 探索中も ほかの API は 探索しているプレイヤーを使う
+        assert!(game.get_player_state().is_ok());
+        assert!(game.generate_password().is_ok());
+        assert!(game.equip("weapon", 3).is_ok());
+        assert!(game.add_item(101).is_ok());
+        // 探索中の せんとう は explore で行う
+        assert!(game.run_battle(0).is_err());
+
+        game.end_exploration();
+        assert!(game.has_player());
+        assert!(game.get_quest_progress().is_ok());
+        let player = game.player.as_ref().unwrap();
+        assert_eq!((player.weapon, player.herbs), (3, 1));
+    }
+
+    #[wasm_bindgen_test]
+    fn test_load_during_exploration() {
+        let mut game = WasmGame::new();
+        let first = Player::new("ゆうてい").to_password_string().unwrap();
+        let second = Player::new("だい").to_password_string().unwrap();
+
+        game.load_from_password(&first).unwrap();
+        assert!(game.explore("search").is_ok());
+        game.load_from_password(&second).unwrap();
+        // 読み込むと 探索は終わる
+        assert!(!game.is_exploring());
+        game.end_exploration();
+        assert_eq!(game.player.as_ref().unwrap().name, Player::new("だい").name);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_npc_message() {
+        let mut game = WasmGame::new();
+        assert!(game.get_npcs().is_ok());
+        assert!(game.get_npc_message("ラダトームのしろ", "おうさま").is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        let message = game.get_npc_message("ラダトームのしろ", "おうさま").unwrap();
+        assert!(message.contains("ゆうてい"));
+        assert!(game.get_npc_message("ラダトームのしろ", "だれか").is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn test_plan_route() {
+        let mut game = WasmGame::new();
+        assert!(game.plan_route("ラダトームのしろ", "ラダトームのまち", vec![]).is_err());
+
+        game.create_player("ゆうてい").unwrap();
+        assert!(game.plan_route("ラダトームのしろ", "ラダトームのまち", vec![]).is_ok());
+        assert!(game.plan_route("ラダトームのしろ", "どこか", vec![]).is_err());
+        assert!(game.plan_route("ラダトームのしろ", "ラダトームのまち", vec!["fly".to_string()]).is_err());
+    }
+
     #[wasm_bindgen_test]
     fn test_battle_without_player() {
         let mut game = WasmGame::new();
@@ -0,0 +1,183 @@
+use crate::constants::world::{
+    Entrance, TERRAIN_LIST, Terrain, WORLD_ENTRANCES, WORLD_HEIGHT, WORLD_MAP, WORLD_WIDTH,
+};
+
+/// 地上マップ（にじのはし などで書き換えられるよう WORLD_MAP から複製して持つ）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct World {
+    tiles: Vec<Terrain>,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        let tiles = WORLD_MAP
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|c| Terrain::from_char(c).unwrap_or(Terrain::Water))
+            .collect();
+        Self { tiles }
+    }
+
+    pub fn width(&self) -> usize {
+        WORLD_WIDTH
+    }
+
+    pub fn height(&self) -> usize {
+        WORLD_HEIGHT
+    }
+
+    pub fn tile_at(&self, x: usize, y: usize) -> Option<Terrain> {
+        if x >= WORLD_WIDTH || y >= WORLD_HEIGHT {
+            return None;
+        }
+        self.tiles.get(y * WORLD_WIDTH + x).copied()
+    }
+
+    /// マップ外は歩けない
+    pub fn is_walkable(&self, x: usize, y: usize) -> bool {
+        self.tile_at(x, y).is_some_and(|t| t.is_walkable())
+    }
+
+    pub fn set_tile(&mut self, x: usize, y: usize, terrain: Terrain) {
+        if x < WORLD_WIDTH && y < WORLD_HEIGHT {
+            self.tiles[y * WORLD_WIDTH + x] = terrain;
+        }
+    }
+
+    pub fn entrance_at(&self, x: usize, y: usize) -> Option<&'static Entrance> {
+        WORLD_ENTRANCES.iter().find(|e| e.x == x && e.y == y)
+    }
+
+    pub fn find_entrance(&self, name: &str) -> Option<&'static Entrance> {
+        WORLD_ENTRANCES.iter().find(|e| e.name == name)
+    }
+
+    /// (x, y) から幅 width・高さ height の範囲を文字で描く（マップ外は切り詰める）
+    pub fn render_region(&self, x: usize, y: usize, width: usize, height: usize) -> Vec<String> {
+        let x_end = (x + width).min(WORLD_WIDTH);
+        let y_end = (y + height).min(WORLD_HEIGHT);
+        (y.min(y_end)..y_end)
+            .map(|row| {
+                (x.min(x_end)..x_end)
+                    .filter_map(|col| self.tile_at(col, row))
+                    .map(|t| t.to_char())
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn render(&self) -> Vec<String> {
+        self.render_region(0, 0, WORLD_WIDTH, WORLD_HEIGHT)
+    }
+}
+
+/// 地形記号の凡例
+pub fn legend() -> String {
+    TERRAIN_LIST
+        .iter()
+        .map(|t| format!("{}={}", t.to_char(), t.as_str()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::world::RAINBOW_BRIDGE_POSITION;
+    use std::collections::{HashSet, VecDeque};
+
+    fn reachable_from(world: &World, start: (usize, usize)) -> HashSet<(usize, usize)> {
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((x, y)) = queue.pop_front() {
+            let neighbors = [
+                (x + 1, y),
+                (x.wrapping_sub(1), y),
+                (x, y + 1),
+                (x, y.wrapping_sub(1)),
+            ];
+            for (nx, ny) in neighbors {
+                if world.is_walkable(nx, ny) && seen.insert((nx, ny)) {
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn test_map_rows_are_rectangular_and_known() {
+        assert_eq!(WORLD_MAP.len(), WORLD_HEIGHT);
+        for row in WORLD_MAP.iter() {
+            assert_eq!(row.chars().count(), WORLD_WIDTH);
+            assert!(row.chars().all(|c| Terrain::from_char(c).is_some()));
+        }
+    }
+
+    #[test]
+    fn test_entrances_match_tiles() {
+        let world = World::new();
+        for entrance in WORLD_ENTRANCES.iter() {
+            assert_eq!(
+                world.tile_at(entrance.x, entrance.y),
+                Some(entrance.terrain),
+                "{}",
+                entrance.name
+            );
+        }
+        assert_eq!(world.tile_at(43, 43), Some(Terrain::Castle));
+        assert_eq!(world.entrance_at(43, 43).unwrap().name, "ラダトームのしろ");
+        assert!(world.tile_at(WORLD_WIDTH, 0).is_none());
+        assert!(!world.is_walkable(0, 0));
+    }
+
+    #[test]
+    fn test_charlock_needs_rainbow_bridge() {
+        let mut world = World::new();
+        let (bx, by) = RAINBOW_BRIDGE_POSITION;
+        assert_eq!(world.tile_at(bx, by), Some(Terrain::Water));
+
+        let south = world.find_entrance("ぬまちのどうくつ（みなみ）").unwrap();
+        let reachable = reachable_from(&world, (south.x, south.y));
+        assert!(!reachable.contains(&(48, 48)));
+
+        world.set_tile(bx, by, Terrain::Bridge);
+        let reachable = reachable_from(&world, (south.x, south.y));
+        assert!(reachable.contains(&(48, 48)));
+    }
+
+    #[test]
+    fn test_north_and_south_are_linked_only_by_swamp_cave() {
+        let world = World::new();
+        let north = reachable_from(&world, (43, 43));
+        for name in [
+            "ラダトームのまち",
+            "ガライのまち",
+            "マイラのむら",
+            "ロトのどうくつ",
+        ] {
+            let e = world.find_entrance(name).unwrap();
+            assert!(north.contains(&(e.x, e.y)), "{}", name);
+        }
+        for name in ["リムルダール", "メルキド", "せいなるほこら"] {
+            let e = world.find_entrance(name).unwrap();
+            assert!(!north.contains(&(e.x, e.y)), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_render_region_clips_to_map() {
+        let world = World::new();
+        let region = world.render_region(41, 41, 5, 3);
+        assert_eq!(region.len(), 3);
+        assert_eq!(region[2].chars().nth(2), Some('C'));
+        assert_eq!(world.render_region(118, 118, 5, 5).len(), 2);
+        assert_eq!(world.render().len(), WORLD_HEIGHT);
+    }
+}
@@ -0,0 +1,204 @@
+use crate::constants::item_weapon::{DEATH_NECKLACE_ID, MAX_HERBS, MAX_KEYS};
+use crate::dungeon::ChestContent;
+use crate::inventory::{InventoryError, add_item};
+use crate::player::Player;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// ダンジョン名・階・座標 で表すマス
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct MapPosition {
+    pub map: String,
+    pub floor: u8,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl MapPosition {
+    pub fn new(map: &str, floor: u8, x: usize, y: usize) -> Self {
+        Self {
+            map: map.to_string(),
+            floor,
+            x,
+            y,
+        }
+    }
+}
+
+/// あけた たからばこ と ひらいた とびら の記録。
+/// ふっかつのじゅもん には残らないので、じゅもんを入れ直したら reset する（たからばこ も とびら も元に戻る）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldState {
+    opened_chests: BTreeSet<MapPosition>,
+    unlocked_doors: BTreeSet<MapPosition>,
+}
+
+impl WorldState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_chest_opened(&self, position: &MapPosition) -> bool {
+        self.opened_chests.contains(position)
+    }
+
+    /// はじめて あけたときだけ true
+    pub fn open_chest(&mut self, position: MapPosition) -> bool {
+        self.opened_chests.insert(position)
+    }
+
+    pub fn is_door_unlocked(&self, position: &MapPosition) -> bool {
+        self.unlocked_doors.contains(position)
+    }
+
+    /// はじめて ひらいたときだけ true
+    pub fn unlock_door(&mut self, position: MapPosition) -> bool {
+        self.unlocked_doors.insert(position)
+    }
+
+    pub fn opened_chests(&self) -> impl Iterator<Item = &MapPosition> {
+        self.opened_chests.iter()
+    }
+
+    pub fn unlocked_doors(&self) -> impl Iterator<Item = &MapPosition> {
+        self.unlocked_doors.iter()
+    }
+
+    pub fn reset(&mut self) {
+        self.opened_chests.clear();
+        self.unlocked_doors.clear();
+    }
+}
+
+/// たからばこ の中身を受け取る結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChestTake {
+    Received(String),
+    Empty,        // ひとつしかない どうぐ を もう持っている
+    Full(String), // もちものが いっぱいで受け取れない（たからばこ は あけないまま）
+}
+
+/// たからばこ の中身を プレイヤーに渡す。どうぐ欄・やくそう・かぎ の上限を超えるときは何もしない
+pub fn take_chest_content(player: &mut Player, content: ChestContent) -> ChestTake {
+    let name = content.name();
+    match content {
+        ChestContent::Gold(gold) => {
+            player.gold = player.gold.saturating_add(gold);
+        }
+        ChestContent::Herb => {
+            if player.herbs >= MAX_HERBS {
+                return ChestTake::Full(name);
+            }
+            player.herbs += 1;
+        }
+        ChestContent::Key => {
+            if player.keys >= MAX_KEYS {
+                return ChestTake::Full(name);
+            }
+            player.keys += 1;
+        }
+        ChestContent::Item(id) => {
+            // 非売品（だいじなもの）は ひとつしか持てない。しのくびかざり は 一度 手に入れたら もう出ない
+            if id == DEATH_NECKLACE_ID && player.flags.has_cursed_necklace {
+                return ChestTake::Empty;
+            }
+            match add_item(player, id) {
+                Err(InventoryError::AlreadyOwned) => return ChestTake::Empty,
+                Err(_) => return ChestTake::Full(name),
+                Ok(()) => {}
+            }
+            if id == DEATH_NECKLACE_ID {
+                player.flags.has_cursed_necklace = true;
+            }
+        }
+        ChestContent::Weapon(id) => {
+            if player.weapon == id {
+                return ChestTake::Empty;
+            }
+            player.weapon = id;
+        }
+    }
+    ChestTake::Received(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::item_weapon::{ERDRICK_TOKEN_ID, TORCH_ID};
+
+    #[test]
+    fn test_state_records_and_resets() {
+        let mut state = WorldState::new();
+        let chest = MapPosition::new("ガライのはか", 2, 8, 5);
+        assert!(state.open_chest(chest.clone()));
+        assert!(!state.open_chest(chest.clone()));
+        assert!(state.is_chest_opened(&chest));
+        assert!(!state.is_chest_opened(&MapPosition::new("ガライのはか", 1, 8, 5)));
+        assert!(state.unlock_door(MapPosition::new("ガライのはか", 3, 4, 3)));
+        assert_eq!(state.unlocked_doors().count(), 1);
+
+        state.reset();
+        assert_eq!(state, WorldState::new());
+    }
+
+    #[test]
+    fn test_take_chest_content_limits() {
+        let mut player = Player::new("ゆうてい");
+        player.gold = u16::MAX - 10;
+        assert_eq!(
+            take_chest_content(&mut player, ChestContent::Gold(120)),
+            ChestTake::Received("120ゴールド".to_string())
+        );
+        assert_eq!(player.gold, u16::MAX);
+
+        player.herbs = MAX_HERBS;
+        assert_eq!(
+            take_chest_content(&mut player, ChestContent::Herb),
+            ChestTake::Full("やくそう".to_string())
+        );
+        player.keys = MAX_KEYS - 1;
+        assert!(matches!(
+            take_chest_content(&mut player, ChestContent::Key),
+            ChestTake::Received(_)
+        ));
+        assert_eq!(player.keys, MAX_KEYS);
+
+        player.items = [ERDRICK_TOKEN_ID, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(
+            take_chest_content(&mut player, ChestContent::Item(ERDRICK_TOKEN_ID)),
+            ChestTake::Empty
+        );
+        assert!(matches!(
+            take_chest_content(&mut player, ChestContent::Item(TORCH_ID)),
+            ChestTake::Received(_)
+        ));
+        player.items = [TORCH_ID; 8];
+        assert_eq!(
+            take_chest_content(&mut player, ChestContent::Item(TORCH_ID)),
+            ChestTake::Full("たいまつ".to_string())
+        );
+
+        assert!(matches!(
+            take_chest_content(&mut player, ChestContent::Weapon(7)),
+            ChestTake::Received(_)
+        ));
+        assert_eq!(player.weapon, 7);
+        assert_eq!(
+            take_chest_content(&mut player, ChestContent::Weapon(7)),
+            ChestTake::Empty
+        );
+
+        // しのくびかざり は 売ったり なくしたりしても 二度と手に入らない
+        player.items = [0; 8];
+        assert!(matches!(
+            take_chest_content(&mut player, ChestContent::Item(DEATH_NECKLACE_ID)),
+            ChestTake::Received(_)
+        ));
+        assert!(player.flags.has_cursed_necklace);
+        player.items = [0; 8];
+        assert_eq!(
+            take_chest_content(&mut player, ChestContent::Item(DEATH_NECKLACE_ID)),
+            ChestTake::Empty
+        );
+    }
+}